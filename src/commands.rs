@@ -1,4 +1,10 @@
-use clap::{Arg, Command};
+use crate::config::{
+    parse_django_version, ApiType, CacheBackend, DbType, Deployment, Feature, FrontendType,
+    PackageManager, ProjectType, RoleModel, Toggle,
+};
+use crate::doctor::OutputFormat;
+use clap::{value_parser, Arg, ArgAction, Command};
+use std::path::PathBuf;
 
 pub fn setup_command() -> Command {
     Command::new("Django Boilerplate Setup")
@@ -14,47 +20,463 @@ pub fn setup_command() -> Command {
             ",
         )
         .about("Configure votre projet Django")
+        .subcommand(new_command())
+        .subcommand(add_command())
+        .subcommand(doctor_command())
+        .subcommand(info_command())
+        .subcommand(templates_command())
+        .subcommand(wheelhouse_command())
         .arg(
-            Arg::new("project_type")
-                .short('t')
-                .long("type")
-                .value_name("PROJECT_TYPE")
-                .help("Type de projet: Backend ou Fullstack"),
+            Arg::new("templates_dir")
+                .long("templates-dir")
+                .value_name("DIR")
+                .value_parser(value_parser!(PathBuf))
+                .global(true)
+                .help(
+                    "Dossier de templates prioritaire sur ~/.config/django_cli/templates et les \
+                     templates intégrés (hors startproject et startapp, produits par django-admin)",
+                ),
+        )
+        // Sans sous-commande, les options de `new` restent acceptées comme avant les
+        // sous-commandes (`django_cli --type backend`), sans encombrer l'aide
+        .args(
+            new_command()
+                .get_arguments()
+                .map(|arg| arg.clone().hide(true)),
+        )
+        .args_conflicts_with_subcommands(true)
+}
+
+/// `new`: creates and configures a new Django project (the default when no subcommand is given).
+pub fn new_command() -> Command {
+    Command::new("new")
+        .about("Créer et configurer un nouveau projet Django")
+        .args(project_args())
+        .arg(
+            Arg::new("allow_system_install")
+                .long("allow-system-install")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("python")
+                .help(
+                    "Installer Python avec le gestionnaire de paquets du système (apt, dnf, ...) \
+                     si aucun interpréteur compatible n'est trouvé, sans confirmation",
+                ),
+        )
+        .arg(
+            Arg::new("no_venv")
+                .long("no-venv")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("venv_path")
+                .help("Utiliser le Python du système au lieu d'un environnement virtuel"),
+        )
+        .arg(
+            Arg::new("venv_path")
+                .long("venv-path")
+                .value_name("DIR")
+                .value_parser(value_parser!(PathBuf))
+                .help("Emplacement de l'environnement virtuel (par défaut : <projet>/.venv)"),
+        )
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .action(ArgAction::SetTrue)
+                .requires("wheelhouse")
+                .help("Installer les dépendances sans accès au réseau, depuis --wheelhouse"),
+        )
+        .arg(
+            Arg::new("wheelhouse")
+                .long("wheelhouse")
+                .value_name("DIR")
+                .value_parser(value_parser!(PathBuf))
+                .requires("offline")
+                .help("Dossier de wheels créé par `django_cli wheelhouse build`"),
         )
         .arg(
-            Arg::new("frontend_type")
-                .short('f')
-                .long("frontend")
-                .value_name("FRONTEND_TYPE")
-                .help("Type de frontend: Django Template ou React")
-                .requires_if("Fullstack", "project_type"),
+            Arg::new("lock")
+                .long("lock")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_venv")
+                .help(
+                    "Générer requirements.lock avec les versions exactes installées et leurs empreintes",
+                ),
         )
         .arg(
-            Arg::new("api_type")
-                .short('a')
-                .long("api")
-                .value_name("API_TYPE")
-                .help("Type d'API: Aucune, REST ou GraphQL"),
+            Arg::new("no_verify")
+                .long("no-verify")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Ne pas vérifier le projet généré (compilation, manage.py check, migrations)",
+                ),
         )
         .arg(
-            Arg::new("db_type")
-                .short('d')
-                .long("database")
-                .value_name("DB_TYPE")
-                .help("Type de base de données: SQLite, PostgreSQL, MySQL"),
+            Arg::new("keep_on_failure")
+                .long("keep-on-failure")
+                .action(ArgAction::SetTrue)
+                .help("Conserver les fichiers générés si une étape échoue (débogage)"),
         )
         .arg(
-            Arg::new("payment")
-                .short('p')
-                .long("payment")
-                .value_name("PAYMENT")
-                .help("Intégrer un système de paiement: Oui ou Non"),
+            Arg::new("dry_run")
+                .long("dry-run")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Afficher les fichiers, modifications et commandes prévus sans rien exécuter",
+                ),
         )
+}
+
+/// `add`: adds a feature to an existing generated project.
+fn add_command() -> Command {
+    Command::new("add")
+        .about("Ajouter une fonctionnalité à un projet Django existant")
+        .arg(
+            Arg::new("feature")
+                .required(true)
+                .value_name("FEATURE")
+                .value_parser(value_parser!(Feature))
+                .ignore_case(true)
+                .help("Fonctionnalité à ajouter"),
+        )
+        .arg(
+            Arg::new("cache_backend")
+                .long("backend")
+                .value_name("CACHE_BACKEND")
+                .value_parser(value_parser!(CacheBackend))
+                .ignore_case(true)
+                .help("Système de cache à configurer (fonctionnalité `cache`)"),
+        )
+        .arg(
+            Arg::new("email_login")
+                .long("email-login")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Identifier les utilisateurs par leur adresse e-mail (fonctionnalité `auth`)",
+                ),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Ajouter `auth` même si les migrations de auth sont déjà appliquées à la base \
+                     de données",
+                ),
+        )
+        .arg(path_arg())
+}
+
+/// `doctor`: checks the environment and the project.
+fn doctor_command() -> Command {
+    Command::new("doctor")
+        .about("Vérifier l'environnement de développement et l'état du projet")
+        .arg(path_arg())
         .arg(
-            Arg::new("email_service")
-                .short('e')
-                .long("email")
-                .value_name("EMAIL_SERVICE")
-                .help("Intégrer un service d'envoi de mails: Oui ou Non"),
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(value_parser!(OutputFormat))
+                .default_value("table")
+                .help("Format du rapport"),
         )
 }
+
+/// `info`: prints information about the CLI and the project.
+fn info_command() -> Command {
+    Command::new("info")
+        .about("Afficher les informations sur la CLI et le projet courant")
+        .arg(path_arg())
+}
+
+/// `templates`: manages the templates used to generate files.
+fn templates_command() -> Command {
+    Command::new("templates")
+        .about("Gérer les templates des fichiers générés")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about(
+                    "Exporter les templates intégrés pour les personnaliser (les fichiers de \
+                     startproject et startapp restent ceux de django-admin)",
+                )
+                .arg(
+                    Arg::new("output")
+                        .required(true)
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Dossier de destination"),
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help("Écraser les fichiers existants"),
+                ),
+        )
+}
+
+/// `wheelhouse`: prepares the wheels of a project for `new --offline`.
+fn wheelhouse_command() -> Command {
+    Command::new("wheelhouse")
+        .about("Préparer les dépendances pour une installation hors ligne")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("build")
+                .about("Télécharger les wheels d'un projet pour `new --offline --wheelhouse`")
+                .arg(
+                    Arg::new("output")
+                        .required(true)
+                        .value_name("DIR")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Dossier de destination des wheels"),
+                )
+                .args(project_args()),
+        )
+}
+
+/// Options describing a project, shared by `new` and `wheelhouse build`.
+fn project_args() -> Vec<Arg> {
+    vec![
+        Arg::new("project_type")
+            .short('t')
+            .long("type")
+            .value_name("PROJECT_TYPE")
+            .value_parser(value_parser!(ProjectType))
+            .ignore_case(true)
+            .help("Type de projet"),
+        Arg::new("frontend_type")
+            .short('f')
+            .long("frontend")
+            .value_name("FRONTEND_TYPE")
+            .value_parser(value_parser!(FrontendType))
+            .ignore_case(true)
+            .help("Type de frontend (projets Fullstack)"),
+        Arg::new("api_type")
+            .short('a')
+            .long("api")
+            .value_name("API_TYPE")
+            .value_parser(value_parser!(ApiType))
+            .ignore_case(true)
+            .help("Type d'API"),
+        Arg::new("db_type")
+            .short('d')
+            .long("database")
+            .value_name("DB_TYPE")
+            .value_parser(value_parser!(DbType))
+            .ignore_case(true)
+            .help("Type de base de données"),
+        Arg::new("db_host")
+            .long("db-host")
+            .value_name("HOST")
+            .help("Hôte par défaut de la base de données (dans DATABASE_URL)"),
+        Arg::new("db_port")
+            .long("db-port")
+            .value_name("PORT")
+            .value_parser(value_parser!(u16))
+            .help("Port par défaut de la base de données (dans DATABASE_URL)"),
+        Arg::new("db_name")
+            .long("db-name")
+            .value_name("NAME")
+            .help("Nom par défaut de la base de données (dans DATABASE_URL)"),
+        Arg::new("auth_enabled")
+            .long("auth")
+            .value_name("AUTH")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Inclure un système d'authentification des utilisateurs"),
+        Arg::new("roles")
+            .long("roles")
+            .value_name("ROLES")
+            .value_parser(value_parser!(RoleModel))
+            .ignore_case(true)
+            .help("Modèle de rôles utilisateurs (avec l'authentification)"),
+        Arg::new("email_login")
+            .long("email-login")
+            .value_name("EMAIL_LOGIN")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Connexion par adresse e-mail au lieu d'un nom d'utilisateur (avec l'authentification)"),
+        Arg::new("payment")
+            .short('p')
+            .long("payment")
+            .value_name("PAYMENT")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Intégrer un système de paiement"),
+        Arg::new("email_service")
+            .short('e')
+            .long("email")
+            .value_name("EMAIL_SERVICE")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Intégrer un service d'envoi de mails"),
+        Arg::new("cache_backend")
+            .long("cache")
+            .value_name("CACHE_BACKEND")
+            .value_parser(value_parser!(CacheBackend))
+            .ignore_case(true)
+            .help("Système de cache"),
+        Arg::new("testing")
+            .long("testing")
+            .value_name("TESTING")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Inclure un système de tests automatisés"),
+        Arg::new("linting")
+            .long("linting")
+            .value_name("LINTING")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Configurer les outils de qualité de code (ruff, mypy)"),
+        Arg::new("split_settings")
+            .long("split-settings")
+            .value_name("SPLIT_SETTINGS")
+            .value_parser(value_parser!(Toggle))
+            .ignore_case(true)
+            .help("Remplacer settings.py par un package settings/ (base, development, production, test)"),
+        Arg::new("deployment")
+            .long("deployment")
+            .value_name("DEPLOYMENT")
+            .value_parser(value_parser!(Deployment))
+            .ignore_case(true)
+            .help("Mode de déploiement"),
+        Arg::new("package_manager")
+            .long("package-manager")
+            .value_name("PACKAGE_MANAGER")
+            .value_parser(value_parser!(PackageManager))
+            .ignore_case(true)
+            .help("Gestionnaire de paquets qui installe les dépendances"),
+        Arg::new("django_version")
+            .long("django-version")
+            .value_name("VERSION")
+            .value_parser(parse_django_version)
+            .help("Version de Django (par défaut : la dernière LTS compatible avec Python)"),
+        Arg::new("python")
+            .long("python")
+            .value_name("PYTHON")
+            .value_parser(value_parser!(PathBuf))
+            .help("Interpréteur Python à utiliser (par défaut : choisi parmi ceux trouvés)"),
+        Arg::new("config")
+            .short('c')
+            .long("config")
+            .value_name("CONFIG_FILE")
+            .help("Fichier de spécification du projet (TOML, YAML ou JSON), sans aucune invite"),
+    ]
+}
+
+fn path_arg() -> Arg {
+    Arg::new("path")
+        .short('C')
+        .long("path")
+        .value_name("PROJECT_DIR")
+        .value_parser(value_parser!(PathBuf))
+        .default_value(".")
+        .help("Répertoire du projet Django")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    fn parse(args: &[&str]) -> Result<clap::ArgMatches, clap::Error> {
+        setup_command()
+            .try_get_matches_from(std::iter::once("django_cli").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn new_reads_the_project_flags() {
+        let matches = parse(&["new", "-t", "BACK", "--database", "pg", "--auth", "oui"]).unwrap();
+        let (name, new) = matches.subcommand().unwrap();
+        assert_eq!(name, "new");
+        assert_eq!(
+            new.get_one::<ProjectType>("project_type"),
+            Some(&ProjectType::Backend)
+        );
+        assert_eq!(new.get_one::<DbType>("db_type"), Some(&DbType::Postgresql));
+        assert_eq!(new.get_one::<Toggle>("auth_enabled"), Some(&Toggle::Yes));
+        assert_eq!(new.get_one::<ApiType>("api_type"), None);
+    }
+
+    #[test]
+    fn add_requires_a_known_feature() {
+        let matches = parse(&["add", "drf", "--path", "shop"]).unwrap();
+        let (name, add) = matches.subcommand().unwrap();
+        assert_eq!(name, "add");
+        assert_eq!(add.get_one::<Feature>("feature"), Some(&Feature::Rest));
+        assert_eq!(add.get_one::<PathBuf>("path"), Some(&PathBuf::from("shop")));
+
+        let missing = parse(&["add"]).unwrap_err();
+        assert_eq!(missing.kind(), ErrorKind::MissingRequiredArgument);
+        let unknown = parse(&["add", "blog"]).unwrap_err();
+        assert_eq!(unknown.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn doctor_and_info_default_to_the_current_directory() {
+        for command in ["doctor", "info"] {
+            let matches = parse(&[command]).unwrap();
+            let (name, sub_matches) = matches.subcommand().unwrap();
+            assert_eq!(name, command);
+            assert_eq!(
+                sub_matches.get_one::<PathBuf>("path"),
+                Some(&PathBuf::from("."))
+            );
+        }
+    }
+
+    #[test]
+    fn subcommands_are_optional_but_checked() {
+        assert!(parse(&[]).unwrap().subcommand().is_none());
+        let unknown = parse(&["deploy"]).unwrap_err();
+        assert_eq!(unknown.kind(), ErrorKind::InvalidSubcommand);
+    }
+
+    #[test]
+    fn new_flags_are_accepted_without_subcommand() {
+        let matches = parse(&["--type", "backend", "--database", "postgres", "--dry-run"]).unwrap();
+        assert!(matches.subcommand().is_none());
+        assert_eq!(
+            matches.get_one::<ProjectType>("project_type"),
+            Some(&ProjectType::Backend)
+        );
+        assert_eq!(
+            matches.get_one::<DbType>("db_type"),
+            Some(&DbType::Postgresql)
+        );
+        assert!(matches.get_flag("dry_run"));
+        assert!(!matches.get_flag("no_venv"));
+
+        let mixed = parse(&["--type", "backend", "add", "auth"]).unwrap_err();
+        assert_eq!(mixed.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn offline_and_wheelhouse_go_together() {
+        let matches = parse(&["new", "--offline", "--wheelhouse", "wheels"]).unwrap();
+        let (_, new) = matches.subcommand().unwrap();
+        assert!(new.get_flag("offline"));
+        assert_eq!(
+            new.get_one::<PathBuf>("wheelhouse"),
+            Some(&PathBuf::from("wheels"))
+        );
+
+        for args in [
+            &["new", "--offline"][..],
+            &["new", "--wheelhouse", "wheels"],
+        ] {
+            let error = parse(args).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                ErrorKind::MissingRequiredArgument,
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn lock_needs_a_project_venv() {
+        assert!(parse(&["new", "--lock", "--venv-path", "../envs/shop"]).is_ok());
+        let error = parse(&["new", "--lock", "--no-venv"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+    }
+}
@@ -1,112 +1,1146 @@
-use crate::commands::setup_command;
-use crate::utils::tools::{get_project_name, sanitize_project_name, select_option};
+use crate::dependencies;
+use crate::error::{self, CliError};
+use crate::utils::check::is_command_available;
+use crate::utils::install::{DjangoRelease, DJANGO_RELEASES};
+use crate::utils::tools::{get_project_name, sanitize_project_name, select_choice, select_from};
+use clap::{ArgMatches, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kind of project to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectType {
+    #[value(alias = "back")]
+    Backend,
+    #[value(alias = "full-stack", alias = "full")]
+    Fullstack,
+}
+
+/// Frontend served alongside a fullstack project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FrontendType {
+    #[value(
+        alias = "django",
+        alias = "template",
+        alias = "templates",
+        alias = "django template"
+    )]
+    DjangoTemplate,
+    #[value(alias = "reactjs")]
+    React,
+}
+
+/// API layer exposed by the project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiType {
+    #[value(alias = "aucune", alias = "aucun", alias = "no", alias = "non")]
+    None,
+    #[value(alias = "drf")]
+    Rest,
+    #[value(alias = "graphene")]
+    Graphql,
+}
+
+/// Database engine used by the project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DbType {
+    #[value(alias = "sqlite3")]
+    Sqlite,
+    #[value(alias = "postgres", alias = "pg")]
+    Postgresql,
+    #[value(alias = "mariadb")]
+    Mysql,
+}
+
+impl DbType {
+    /// Default server port, `None` for file-based engines.
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            DbType::Sqlite => None,
+            DbType::Postgresql => Some(5432),
+            DbType::Mysql => Some(3306),
+        }
+    }
+
+    /// Default administrator account of the server.
+    pub fn default_user(&self) -> &'static str {
+        match self {
+            DbType::Sqlite => "",
+            DbType::Postgresql => "postgres",
+            DbType::Mysql => "root",
+        }
+    }
+
+    /// Python driver required by the backend, if it is not bundled with Python.
+    pub fn driver_requirement(&self) -> Option<&'static str> {
+        match self {
+            DbType::Sqlite => None,
+            DbType::Postgresql => Some(dependencies::PSYCOPG),
+            DbType::Mysql => Some(dependencies::MYSQLCLIENT),
+        }
+    }
+}
+
+/// Cache backend configured in settings.py.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheBackend {
+    #[value(alias = "aucun", alias = "no", alias = "non", alias = "false")]
+    None,
+    Redis,
+    #[value(alias = "memcache", alias = "pymemcache")]
+    Memcached,
+    #[value(alias = "local", alias = "local-memory", alias = "memoire")]
+    Locmem,
+    #[value(alias = "db", alias = "base-de-donnees")]
+    Database,
+}
+
+impl CacheBackend {
+    /// Django cache backend class, `None` when no cache is configured.
+    pub fn backend(&self) -> Option<&'static str> {
+        match self {
+            CacheBackend::None => None,
+            CacheBackend::Redis => Some("django.core.cache.backends.redis.RedisCache"),
+            CacheBackend::Memcached => Some("django.core.cache.backends.memcached.PyMemcacheCache"),
+            CacheBackend::Locmem => Some("django.core.cache.backends.locmem.LocMemCache"),
+            CacheBackend::Database => Some("django.core.cache.backends.db.DatabaseCache"),
+        }
+    }
+
+    /// Python client library required by the backend, if any.
+    pub fn client_requirement(&self) -> Option<&'static str> {
+        match self {
+            CacheBackend::Redis => Some(dependencies::REDIS),
+            CacheBackend::Memcached => Some(dependencies::PYMEMCACHE),
+            _ => None,
+        }
+    }
+}
+
+/// How users are split into roles when authentication is enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoleModel {
+    #[value(alias = "1", alias = "standard", alias = "utilisateur")]
+    Single,
+    #[value(alias = "2", alias = "admin", alias = "utilisateur-admin")]
+    UserAdmin,
+    #[value(alias = "plus", alias = "personnalise", alias = "personnalisé")]
+    Custom,
+}
+
+/// Deployment target of the project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Deployment {
+    Docker,
+    #[value(
+        alias = "manuel",
+        alias = "manuelle",
+        alias = "server",
+        alias = "serveur"
+    )]
+    Manual,
+}
+
+/// Tool that manages the project dependencies and installs them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackageManager {
+    Pip,
+    Uv,
+    Poetry,
+    Pdm,
+    Pipenv,
+}
+
+impl PackageManager {
+    /// Executable of the tool, `None` for pip, which runs from the project interpreter.
+    pub fn program(&self) -> Option<&'static str> {
+        match self {
+            PackageManager::Pip => None,
+            PackageManager::Uv => Some("uv"),
+            PackageManager::Poetry => Some("poetry"),
+            PackageManager::Pdm => Some("pdm"),
+            PackageManager::Pipenv => Some("pipenv"),
+        }
+    }
+
+    /// Whether the tool can be run on this system. pip is always considered available, as
+    /// it comes with the virtual environment.
+    pub fn is_available(&self) -> bool {
+        self.program().is_none_or(is_command_available)
+    }
+
+    /// Package managers that can be used on this system.
+    pub fn available() -> Vec<PackageManager> {
+        PackageManager::value_variants()
+            .iter()
+            .copied()
+            .filter(PackageManager::is_available)
+            .collect()
+    }
+
+    /// Package manager of an existing project, recognized from its manifest and lock files.
+    pub fn detect(dir: &Path) -> PackageManager {
+        if dir.join("Pipfile").is_file() {
+            return PackageManager::Pipenv;
+        }
+
+        let pyproject = fs::read_to_string(dir.join("pyproject.toml")).unwrap_or_default();
+        if pyproject.contains("[tool.poetry") || dir.join("poetry.lock").is_file() {
+            PackageManager::Poetry
+        } else if pyproject.contains("[tool.pdm") || dir.join("pdm.lock").is_file() {
+            PackageManager::Pdm
+        } else if pyproject.contains("[tool.uv") || dir.join("uv.lock").is_file() {
+            PackageManager::Uv
+        } else {
+            PackageManager::Pip
+        }
+    }
+}
+
+/// Feature that can be added to an existing project with `django_cli add`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Feature {
+    #[value(alias = "drf")]
+    Rest,
+    #[value(alias = "graphene")]
+    Graphql,
+    #[value(alias = "mail")]
+    Email,
+    Cache,
+    #[value(alias = "reactjs")]
+    React,
+    #[value(alias = "authentication", alias = "authentification")]
+    Auth,
+    #[value(alias = "paiement")]
+    Payment,
+}
+
+/// Yes/no answer accepted on the command line and in spec files, in English or French.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Toggle {
+    #[value(alias = "oui", alias = "o", alias = "y", alias = "true")]
+    Yes,
+    #[value(alias = "non", alias = "n", alias = "false")]
+    No,
+}
+
+impl From<Toggle> for bool {
+    fn from(toggle: Toggle) -> bool {
+        toggle == Toggle::Yes
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ProjectType::Backend => "Backend",
+            ProjectType::Fullstack => "Fullstack",
+        })
+    }
+}
+
+impl fmt::Display for FrontendType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FrontendType::DjangoTemplate => "Django Template",
+            FrontendType::React => "React",
+        })
+    }
+}
+
+impl fmt::Display for ApiType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ApiType::None => "Aucune",
+            ApiType::Rest => "REST",
+            ApiType::Graphql => "GraphQL",
+        })
+    }
+}
+
+impl fmt::Display for DbType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DbType::Sqlite => "SQLite",
+            DbType::Postgresql => "PostgreSQL",
+            DbType::Mysql => "MySQL",
+        })
+    }
+}
+
+impl fmt::Display for CacheBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CacheBackend::None => "Aucun",
+            CacheBackend::Redis => "Redis",
+            CacheBackend::Memcached => "Memcached",
+            CacheBackend::Locmem => "Mémoire locale",
+            CacheBackend::Database => "Base de données",
+        })
+    }
+}
+
+impl fmt::Display for RoleModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            RoleModel::Single => "1 (Utilisateur standard)",
+            RoleModel::UserAdmin => "2 (Utilisateur et Admin)",
+            RoleModel::Custom => "Plus (rôles personnalisés)",
+        })
+    }
+}
+
+impl fmt::Display for Deployment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Deployment::Docker => "Docker",
+            Deployment::Manual => "Configuration manuelle (serveur)",
+        })
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            PackageManager::Pip => "pip (requirements.txt)",
+            PackageManager::Uv => "uv",
+            PackageManager::Poetry => "Poetry",
+            PackageManager::Pdm => "PDM",
+            PackageManager::Pipenv => "Pipenv",
+        })
+    }
+}
+
+impl Feature {
+    /// Python package the feature adds to `requirements.txt`, if any. The cache client depends
+    /// on the backend, see `CacheBackend::client_requirement`.
+    pub fn requirement(&self) -> Option<&'static str> {
+        match self {
+            Feature::Rest => Some(dependencies::DJANGO_REST_FRAMEWORK),
+            Feature::Graphql => Some(dependencies::GRAPHENE_DJANGO),
+            // django-cors-headers pour gérer les requêtes CORS avec React
+            Feature::React => Some(dependencies::DJANGO_CORS_HEADERS),
+            Feature::Payment => Some(dependencies::STRIPE),
+            Feature::Email | Feature::Cache | Feature::Auth => None,
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Feature::Rest => "API REST",
+            Feature::Graphql => "API GraphQL",
+            Feature::Email => "Service d'envoi de mails",
+            Feature::Cache => "Système de cache",
+            Feature::React => "Frontend React",
+            Feature::Auth => "Authentification",
+            Feature::Payment => "Système de paiement",
+        })
+    }
+}
+
+impl fmt::Display for Toggle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Toggle::Yes => "Oui",
+            Toggle::No => "Non",
+        })
+    }
+}
 
 #[allow(dead_code)]
+#[derive(Serialize)]
 pub struct ProjectConfig {
     pub project_name: String,
-    pub project_type: String,
-    pub frontend_type: String,
-    pub api_type: String,
-    pub db_type: String,
-    pub auth_enabled: String,
-    pub roles: String,
-    pub payment: String,
-    pub email_service: String,
-    pub cache_system: String,
-    pub testing: String,
-    pub deployment: String,
-}
-
-pub fn get_project_config() -> ProjectConfig {
-    let matches = setup_command().get_matches();
+    pub project_type: ProjectType,
+    pub frontend_type: Option<FrontendType>,
+    pub api_type: ApiType,
+    pub db_type: DbType,
+    pub db_host: Option<String>,
+    pub db_port: Option<u16>,
+    pub db_name: Option<String>,
+    pub auth_enabled: bool,
+    pub roles: Option<RoleModel>,
+    /// Users log in with their email address instead of a username (with authentication).
+    pub email_login: bool,
+    pub payment: bool,
+    pub email_service: bool,
+    pub cache_backend: CacheBackend,
+    pub testing: bool,
+    pub linting: bool,
+    /// Replace settings.py with a `settings/` package: base, development, production and test.
+    pub split_settings: bool,
+    pub deployment: Deployment,
+    pub package_manager: PackageManager,
+    /// Django release requested with `--django-version`; by default, the newest LTS release
+    /// supporting the interpreter.
+    pub django_version: Option<&'static DjangoRelease>,
+    /// Interpreter given with `--python`; by default, one is chosen among those found on the machine.
+    pub python: Option<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// Features selected for this project, applied by the same generators as `django_cli add`.
+    pub fn features(&self) -> Vec<Feature> {
+        let mut features = Vec::new();
+
+        if self.auth_enabled {
+            features.push(Feature::Auth);
+        }
+        match self.api_type {
+            ApiType::Rest => features.push(Feature::Rest),
+            ApiType::Graphql => features.push(Feature::Graphql),
+            ApiType::None => {}
+        }
+        if self.payment {
+            features.push(Feature::Payment);
+        }
+        if self.email_service {
+            features.push(Feature::Email);
+        }
+        if self.cache_backend != CacheBackend::None {
+            features.push(Feature::Cache);
+        }
+        if self.frontend_type == Some(FrontendType::React) {
+            features.push(Feature::React);
+        }
+
+        features
+    }
+
+    /// Requirements of the generated `requirements.txt`: the Django release, the database
+    /// driver, django-environ and the packages of the selected features.
+    pub fn requirements(&self, django: &DjangoRelease) -> Vec<String> {
+        let mut requirements = vec![django.requirement()];
+
+        requirements.extend(self.db_type.driver_requirement().map(str::to_string));
+        requirements.push(dependencies::DJANGO_ENVIRON.to_string());
+        for feature in self.features() {
+            requirements.extend(feature.requirement().map(str::to_string));
+        }
+        requirements.extend(self.cache_backend.client_requirement().map(str::to_string));
+
+        requirements
+    }
+
+    /// Development tools installed with the project: pytest and coverage for `testing`, ruff
+    /// and mypy for `linting`.
+    pub fn dev_requirements(&self) -> Vec<&'static str> {
+        let mut requirements = Vec::new();
+
+        if self.testing {
+            requirements.extend([
+                dependencies::PYTEST,
+                dependencies::PYTEST_DJANGO,
+                dependencies::COVERAGE,
+            ]);
+        }
+        if self.linting {
+            requirements.extend([
+                dependencies::RUFF,
+                dependencies::MYPY,
+                dependencies::DJANGO_STUBS,
+            ]);
+        }
+
+        requirements
+    }
+
+    /// Generation options of the selected features.
+    pub fn feature_options(&self) -> FeatureOptions {
+        FeatureOptions {
+            cache_backend: self.cache_backend,
+            email_login: self.email_login,
+        }
+    }
+}
+
+/// Choices that refine how a feature is generated by `apply_feature`.
+pub struct FeatureOptions {
+    pub cache_backend: CacheBackend,
+    /// The custom user model of `auth` is identified by its email address.
+    pub email_login: bool,
+}
+
+pub fn get_project_config(matches: &ArgMatches) -> error::Result<ProjectConfig> {
+    // Mode non interactif : tout provient du fichier de spécification
+    if let Some(path) = matches.get_one::<String>("config") {
+        return load_project_spec(Path::new(path), matches).map_err(CliError::InvalidConfig);
+    }
 
     // Demander le nom du projet
-    let mut project_name = get_project_name();
-    project_name = sanitize_project_name(&project_name);
+    let name = get_project_name()?;
+    let project_name = sanitize_project_name(&name);
+    if !is_valid_project_name(&project_name) {
+        return Err(CliError::InvalidConfig(vec![format!(
+            "le nom du projet `{}` doit contenir au moins une lettre ou un chiffre",
+            name.trim()
+        )]));
+    }
 
     let project_type = matches
-        .get_one::<String>("project_type")
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| select_option("Type de projet", &["Backend", "Fullstack"]));
+        .get_one::<ProjectType>("project_type")
+        .copied()
+        .map(Ok)
+        .unwrap_or_else(|| select_choice("Type de projet"))?;
 
-    let frontend_type = if project_type == "Fullstack" {
-        matches
-            .get_one::<String>("frontend_type")
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| select_option("Type de frontend", &["Django Template", "React"]))
+    let frontend_type = if project_type == ProjectType::Fullstack {
+        Some(
+            matches
+                .get_one::<FrontendType>("frontend_type")
+                .copied()
+                .map(Ok)
+                .unwrap_or_else(|| select_choice("Type de frontend"))?,
+        )
     } else {
-        "N/A".to_string()
+        None
     };
 
     let api_type = matches
-        .get_one::<String>("api_type")
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| select_option("Type d'API", &["Aucune", "REST", "GraphQL"]));
+        .get_one::<ApiType>("api_type")
+        .copied()
+        .map(Ok)
+        .unwrap_or_else(|| select_choice("Type d'API"))?;
 
     let db_type = matches
-        .get_one::<String>("db_type")
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| {
-            select_option(
-                "Type de base de données",
-                &["SQLite", "PostgreSQL", "MySQL"],
-            )
-        });
+        .get_one::<DbType>("db_type")
+        .copied()
+        .map(Ok)
+        .unwrap_or_else(|| select_choice("Type de base de données"))?;
+    let db_host = matches.get_one::<String>("db_host").cloned();
+    let db_port = matches.get_one::<u16>("db_port").copied();
+    let db_name = matches.get_one::<String>("db_name").cloned();
 
-    let auth_enabled = select_option(
-        "Souhaitez-vous inclure un système d'authentification des utilisateurs ?",
-        &["Oui", "Non"],
-    );
-    let roles = if auth_enabled == "Oui" {
-        select_option(
-            "Combien de rôles utilisateurs souhaitez-vous gérer ?",
-            &[
-                "1 (Utilisateur standard)",
-                "2 (Utilisateur et Admin)",
-                "Plus (rôles personnalisés)",
-            ],
+    let auth_enabled = matches
+        .get_one::<Toggle>("auth_enabled")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| {
+            ask_toggle("Souhaitez-vous inclure un système d'authentification des utilisateurs ?")
+        })?;
+    let roles = if auth_enabled {
+        Some(
+            matches
+                .get_one::<RoleModel>("roles")
+                .copied()
+                .map(Ok)
+                .unwrap_or_else(|| {
+                    select_choice("Combien de rôles utilisateurs souhaitez-vous gérer ?")
+                })?,
         )
     } else {
-        "Aucun".to_string()
+        None
+    };
+    let email_login = if auth_enabled {
+        matches
+            .get_one::<Toggle>("email_login")
+            .map(|&toggle| toggle.into())
+            .map(Ok)
+            .unwrap_or_else(|| {
+                ask_toggle(
+                    "Les utilisateurs se connectent-ils avec leur adresse e-mail plutôt qu'un nom d'utilisateur ?",
+                )
+            })?
+    } else {
+        false
     };
 
     let payment = matches
-        .get_one::<String>("payment")
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| select_option("Intégrer un système de paiement", &["Oui", "Non"]));
+        .get_one::<Toggle>("payment")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| ask_toggle("Intégrer un système de paiement"))?;
 
     let email_service = matches
-        .get_one::<String>("email_service")
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| select_option("Intégrer un service d'envoi de mails", &["Oui", "Non"]));
+        .get_one::<Toggle>("email_service")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| ask_toggle("Intégrer un service d'envoi de mails"))?;
 
-    let cache_system = select_option(
-        "Souhaitez-vous configurer un système de cache pour améliorer les performances ?",
-        &["Oui", "Non"],
-    );
+    let cache_backend = matches
+        .get_one::<CacheBackend>("cache_backend")
+        .copied()
+        .map(Ok)
+        .unwrap_or_else(|| {
+            select_choice(
+                "Quel système de cache souhaitez-vous configurer pour améliorer les performances ?",
+            )
+        })?;
 
-    let testing = select_option(
-        "Souhaitez-vous inclure un système de tests automatisés dès le départ ?",
-        &["Oui", "Non"],
-    );
+    let testing = matches
+        .get_one::<Toggle>("testing")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| {
+            ask_toggle("Souhaitez-vous inclure un système de tests automatisés dès le départ ?")
+        })?;
+
+    let linting = matches
+        .get_one::<Toggle>("linting")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| {
+            ask_toggle("Souhaitez-vous configurer les outils de qualité de code (ruff, mypy) ?")
+        })?;
+
+    let split_settings = matches
+        .get_one::<Toggle>("split_settings")
+        .map(|&toggle| toggle.into())
+        .map(Ok)
+        .unwrap_or_else(|| {
+            ask_toggle(
+                "Souhaitez-vous séparer les settings par environnement (développement, production, tests) ?",
+            )
+        })?;
+
+    let deployment = matches
+        .get_one::<Deployment>("deployment")
+        .copied()
+        .map(Ok)
+        .unwrap_or_else(|| select_choice("Comment souhaitez-vous déployer votre application ?"))?;
+
+    // Seuls les gestionnaires installés sont proposés ; pip est toujours disponible
+    let package_manager = match matches.get_one::<PackageManager>("package_manager") {
+        Some(&package_manager) => package_manager,
+        None => match PackageManager::available().as_slice() {
+            [package_manager] => *package_manager,
+            available => select_from(
+                "Quel gestionnaire de paquets souhaitez-vous utiliser ?",
+                available,
+            )?,
+        },
+    };
+
+    Ok(ProjectConfig {
+        project_name,
+        project_type,
+        frontend_type,
+        api_type,
+        db_type,
+        db_host,
+        db_port,
+        db_name,
+        auth_enabled,
+        roles,
+        email_login,
+        payment,
+        email_service,
+        cache_backend,
+        testing,
+        linting,
+        split_settings,
+        deployment,
+        package_manager,
+        django_version: matches.get_one::<&DjangoRelease>("django_version").copied(),
+        python: matches.get_one::<PathBuf>("python").cloned(),
+    })
+}
+
+fn ask_toggle(prompt: &str) -> error::Result<bool> {
+    select_choice::<Toggle>(prompt).map(bool::from)
+}
+
+/// A scalar read from a spec file. Yes/no fields also accept a plain boolean.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SpecValue {
+    Bool(bool),
+    Text(String),
+}
+
+/// Raw content of a project spec file, before validation.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectSpec {
+    project_name: Option<String>,
+    project_type: Option<SpecValue>,
+    frontend_type: Option<SpecValue>,
+    api_type: Option<SpecValue>,
+    db_type: Option<SpecValue>,
+    db_host: Option<String>,
+    db_port: Option<u16>,
+    db_name: Option<String>,
+    auth_enabled: Option<SpecValue>,
+    roles: Option<SpecValue>,
+    email_login: Option<SpecValue>,
+    payment: Option<SpecValue>,
+    email_service: Option<SpecValue>,
+    #[serde(alias = "cache_system")]
+    cache_backend: Option<SpecValue>,
+    testing: Option<SpecValue>,
+    linting: Option<SpecValue>,
+    split_settings: Option<SpecValue>,
+    deployment: Option<SpecValue>,
+    package_manager: Option<SpecValue>,
+    django_version: Option<String>,
+    python: Option<PathBuf>,
+}
+
+/// Builds a complete `ProjectConfig` from a TOML, YAML or JSON spec file without reading stdin.
+///
+/// Values passed explicitly on the command line take precedence over the file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the spec file; its extension selects the format.
+/// * `matches` - The parsed command line arguments.
+///
+/// # Returns
+///
+/// * `Ok(ProjectConfig)` if every field is present and valid,
+/// * `Err(errors)` listing every missing or invalid field otherwise.
+pub fn load_project_spec(path: &Path, matches: &ArgMatches) -> Result<ProjectConfig, Vec<String>> {
+    let spec = parse_spec_file(path).map_err(|error| vec![error])?;
+    validate_spec(spec, matches)
+}
+
+fn parse_spec_file(path: &Path) -> Result<ProjectSpec, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("impossible de lire {} : {}", path.display(), err))?;
 
-    let deployment = select_option(
-        "Comment souhaitez-vous déployer votre application ?",
-        &["Docker", "Configuration manuelle (serveur)"],
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("toml") => toml::from_str(&content).map_err(|err| err.to_string()),
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|err| err.to_string()),
+        Some("json") => serde_json::from_str(&content).map_err(|err| err.to_string()),
+        _ => Err(format!(
+            "format non pris en charge pour {} (extensions acceptées : .toml, .yaml, .yml, .json)",
+            path.display()
+        )),
+    }
+}
+
+/// Whether a sanitized project name is usable: `sanitize_project_name` turns `---` into `___`,
+/// which names nothing.
+fn is_valid_project_name(name: &str) -> bool {
+    name.chars().any(char::is_alphanumeric)
+}
+
+fn validate_spec(spec: ProjectSpec, matches: &ArgMatches) -> Result<ProjectConfig, Vec<String>> {
+    let mut errors = Vec::new();
+
+    // Le nom est validé une fois nettoyé, tel qu'il servira de dossier et de package
+    let project_name = match spec.project_name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let project_name = sanitize_project_name(name);
+            if is_valid_project_name(&project_name) {
+                Some(project_name)
+            } else {
+                errors.push(format!(
+                    "champ `project_name` : `{}` doit contenir au moins une lettre ou un chiffre",
+                    name
+                ));
+                None
+            }
+        }
+        _ => {
+            errors.push("champ `project_name` manquant".to_string());
+            None
+        }
+    };
+
+    let project_type =
+        resolve_choice::<ProjectType>(matches, "project_type", spec.project_type, &mut errors);
+    let frontend_type = if project_type == Some(ProjectType::Fullstack) {
+        resolve_choice(matches, "frontend_type", spec.frontend_type, &mut errors).map(Some)
+    } else {
+        Some(None)
+    };
+    let api_type = resolve_choice(matches, "api_type", spec.api_type, &mut errors);
+    let db_type = resolve_choice(matches, "db_type", spec.db_type, &mut errors);
+    let db_host = matches
+        .get_one::<String>("db_host")
+        .cloned()
+        .or(spec.db_host);
+    let db_port = matches.get_one::<u16>("db_port").copied().or(spec.db_port);
+    let db_name = matches
+        .get_one::<String>("db_name")
+        .cloned()
+        .or(spec.db_name);
+    let auth_enabled = resolve_toggle(matches, "auth_enabled", spec.auth_enabled, &mut errors);
+    let roles = if auth_enabled == Some(true) {
+        resolve_choice(matches, "roles", spec.roles, &mut errors).map(Some)
+    } else {
+        Some(None)
+    };
+    let email_login = if auth_enabled == Some(true) {
+        resolve_choice_or(
+            matches,
+            "email_login",
+            spec.email_login,
+            Toggle::No,
+            &mut errors,
+        )
+        .map(bool::from)
+    } else {
+        Some(false)
+    };
+    let payment = resolve_toggle(matches, "payment", spec.payment, &mut errors);
+    let email_service = resolve_toggle(matches, "email_service", spec.email_service, &mut errors);
+    let cache_backend = resolve_choice(matches, "cache_backend", spec.cache_backend, &mut errors);
+    let testing = resolve_toggle(matches, "testing", spec.testing, &mut errors);
+    let deployment = resolve_choice(matches, "deployment", spec.deployment, &mut errors);
+    let linting = resolve_choice_or(matches, "linting", spec.linting, Toggle::No, &mut errors)
+        .map(bool::from);
+    let split_settings = resolve_choice_or(
+        matches,
+        "split_settings",
+        spec.split_settings,
+        Toggle::No,
+        &mut errors,
+    )
+    .map(bool::from);
+    let package_manager = resolve_choice_or(
+        matches,
+        "package_manager",
+        spec.package_manager,
+        PackageManager::Pip,
+        &mut errors,
     );
+    let django_version = match (
+        matches.get_one::<&DjangoRelease>("django_version"),
+        spec.django_version,
+    ) {
+        (Some(&release), _) => Some(Some(release)),
+        (None, Some(version)) => match parse_django_version(&version) {
+            Ok(release) => Some(Some(release)),
+            Err(error) => {
+                errors.push(format!("champ `django_version` : {}", error));
+                None
+            }
+        },
+        (None, None) => Some(None),
+    };
+
+    let (
+        Some(project_name),
+        Some(project_type),
+        Some(frontend_type),
+        Some(api_type),
+        Some(db_type),
+        Some(auth_enabled),
+        Some(roles),
+        Some(email_login),
+        Some(payment),
+        Some(email_service),
+        Some(cache_backend),
+        Some(testing),
+        Some(linting),
+        Some(split_settings),
+        Some(deployment),
+        Some(package_manager),
+        Some(django_version),
+    ) = (
+        project_name,
+        project_type,
+        frontend_type,
+        api_type,
+        db_type,
+        auth_enabled,
+        roles,
+        email_login,
+        payment,
+        email_service,
+        cache_backend,
+        testing,
+        linting,
+        split_settings,
+        deployment,
+        package_manager,
+        django_version,
+    )
+    else {
+        return Err(errors);
+    };
 
-    ProjectConfig {
+    Ok(ProjectConfig {
         project_name,
         project_type,
         frontend_type,
         api_type,
         db_type,
+        db_host,
+        db_port,
+        db_name,
         auth_enabled,
         roles,
+        email_login,
         payment,
         email_service,
-        cache_system,
+        cache_backend,
         testing,
+        linting,
+        split_settings,
         deployment,
+        package_manager,
+        django_version,
+        python: matches
+            .get_one::<PathBuf>("python")
+            .cloned()
+            .or(spec.python),
+    })
+}
+
+/// Parses a `--django-version` value (`4.2`, `5.1.3`) into a release of the compatibility table.
+pub fn parse_django_version(version: &str) -> Result<&'static DjangoRelease, String> {
+    DjangoRelease::find(version).ok_or_else(|| {
+        format!(
+            "version de Django inconnue `{}` (versions possibles : {})",
+            version,
+            DJANGO_RELEASES
+                .iter()
+                .map(|release| release.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })
+}
+
+/// Configuration of a minimal SQLite backend project, with the spec fields of `overrides` (a TOML
+/// table) taking precedence. Used by the tests of the generators.
+#[cfg(test)]
+pub fn test_config(overrides: &str) -> ProjectConfig {
+    let mut spec: toml::Table = toml::from_str(
+        "project_name = \"shop\"\nproject_type = \"backend\"\napi_type = \"none\"\n\
+         db_type = \"sqlite\"\nauth_enabled = false\npayment = false\n\
+         email_service = false\ncache_backend = \"none\"\ntesting = false\n\
+         deployment = \"manual\"\n",
+    )
+    .unwrap();
+    spec.extend(toml::from_str::<toml::Table>(overrides).unwrap());
+    let spec = ProjectSpec::deserialize(toml::Value::Table(spec)).unwrap();
+    let matches = crate::commands::new_command().get_matches_from(["new"]);
+    validate_spec(spec, &matches).unwrap()
+}
+
+/// Resolves an enum field, preferring the command line flag of the same name over the spec value.
+/// Records an error and returns `None` when the value is missing or invalid.
+fn resolve_choice<T>(
+    matches: &ArgMatches,
+    field: &str,
+    value: Option<SpecValue>,
+    errors: &mut Vec<String>,
+) -> Option<T>
+where
+    T: ValueEnum + Clone + Send + Sync + 'static,
+{
+    if let Ok(Some(flag)) = matches.try_get_one::<T>(field) {
+        return Some(flag.clone());
+    }
+
+    let text = match value {
+        Some(SpecValue::Text(text)) => text,
+        Some(SpecValue::Bool(flag)) => flag.to_string(),
+        None => {
+            errors.push(format!(
+                "champ `{}` manquant (valeurs possibles : {})",
+                field,
+                possible_values::<T>()
+            ));
+            return None;
+        }
+    };
+
+    match T::from_str(text.trim(), true) {
+        Ok(choice) => Some(choice),
+        Err(_) => {
+            errors.push(format!(
+                "champ `{}` : valeur invalide `{}` (valeurs possibles : {})",
+                field,
+                text,
+                possible_values::<T>()
+            ));
+            None
+        }
+    }
+}
+
+/// Resolves an optional enum field like `resolve_choice`, using `default` when neither the flag
+/// nor the spec value is given.
+fn resolve_choice_or<T>(
+    matches: &ArgMatches,
+    field: &str,
+    value: Option<SpecValue>,
+    default: T,
+    errors: &mut Vec<String>,
+) -> Option<T>
+where
+    T: ValueEnum + Clone + Send + Sync + 'static,
+{
+    match value {
+        None if matches.try_get_one::<T>(field).ok().flatten().is_none() => Some(default),
+        value => resolve_choice(matches, field, value, errors),
+    }
+}
+
+/// Resolves a yes/no field. Booleans and every `Toggle` spelling are accepted.
+fn resolve_toggle(
+    matches: &ArgMatches,
+    field: &str,
+    value: Option<SpecValue>,
+    errors: &mut Vec<String>,
+) -> Option<bool> {
+    resolve_choice::<Toggle>(matches, field, value, errors).map(bool::from)
+}
+
+fn possible_values<T: ValueEnum>() -> String {
+    T::value_variants()
+        .iter()
+        .filter_map(|variant| variant.to_possible_value())
+        .map(|value| value.get_name().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::new_command;
+    use clap::Command;
+
+    fn no_flags() -> ArgMatches {
+        Command::new("django_cli").get_matches_from(["django_cli"])
+    }
+
+    #[test]
+    fn choices_accept_aliases_in_any_case() {
+        assert_eq!(
+            ProjectType::from_str("Full-Stack", true),
+            Ok(ProjectType::Fullstack)
+        );
+        assert_eq!(
+            FrontendType::from_str("django template", true),
+            Ok(FrontendType::DjangoTemplate)
+        );
+        assert_eq!(ApiType::from_str("DRF", true), Ok(ApiType::Rest));
+        assert_eq!(ApiType::from_str("aucune", true), Ok(ApiType::None));
+        assert_eq!(DbType::from_str("pg", true), Ok(DbType::Postgresql));
+        assert_eq!(DbType::from_str("MariaDB", true), Ok(DbType::Mysql));
+        assert_eq!(RoleModel::from_str("2", true), Ok(RoleModel::UserAdmin));
+        assert_eq!(
+            RoleModel::from_str("personnalisé", true),
+            Ok(RoleModel::Custom)
+        );
+        assert_eq!(
+            Deployment::from_str("serveur", true),
+            Ok(Deployment::Manual)
+        );
+        assert_eq!(Toggle::from_str("Oui", true), Ok(Toggle::Yes));
+        assert_eq!(Toggle::from_str("false", true), Ok(Toggle::No));
+        assert!(DbType::from_str("oracle", true).is_err());
+    }
+
+    #[test]
+    fn toggles_accept_booleans_and_words() {
+        let mut errors = Vec::new();
+        let cases = [
+            (SpecValue::Bool(true), Some(true)),
+            (SpecValue::Bool(false), Some(false)),
+            (SpecValue::Text(" non ".to_string()), Some(false)),
+            (SpecValue::Text("y".to_string()), Some(true)),
+            (SpecValue::Text("peut-être".to_string()), None),
+        ];
+        for (value, expected) in cases {
+            let toggle = resolve_toggle(&no_flags(), "testing", Some(value), &mut errors);
+            assert_eq!(toggle, expected);
+        }
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].contains("valeur invalide `peut-être`"),
+            "{}",
+            errors[0]
+        );
+        assert!(errors[0].contains("yes, no"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn optional_choices_fall_back_to_their_default() {
+        // Les matches de `no_flags` ne déclarent pas l'argument : pas de panique en debug
+        let mut errors = Vec::new();
+        let default = resolve_choice_or(
+            &no_flags(),
+            "package_manager",
+            None,
+            PackageManager::Pip,
+            &mut errors,
+        );
+        let given = resolve_choice_or(
+            &no_flags(),
+            "package_manager",
+            Some(SpecValue::Text("UV".to_string())),
+            PackageManager::Pip,
+            &mut errors,
+        );
+        assert_eq!(default, Some(PackageManager::Pip));
+        assert_eq!(given, Some(PackageManager::Uv));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn missing_and_invalid_choices_are_all_reported() {
+        let mut errors = Vec::new();
+        let db_type = resolve_choice::<DbType>(
+            &no_flags(),
+            "db_type",
+            Some(SpecValue::Text("oracle".to_string())),
+            &mut errors,
+        );
+        let deployment = resolve_choice::<Deployment>(&no_flags(), "deployment", None, &mut errors);
+
+        assert_eq!(db_type, None);
+        assert_eq!(deployment, None);
+        assert_eq!(
+            errors,
+            [
+                "champ `db_type` : valeur invalide `oracle` (valeurs possibles : sqlite, postgresql, mysql)",
+                "champ `deployment` manquant (valeurs possibles : docker, manual)",
+            ]
+        );
+    }
+
+    fn project_name_errors(name: &str) -> Vec<String> {
+        let spec: ProjectSpec = toml::from_str(&format!(
+            "project_name = {:?}\nproject_type = \"backend\"\napi_type = \"rest\"\n\
+             db_type = \"sqlite\"\nauth_enabled = false\npayment = false\n\
+             email_service = false\ncache_backend = \"none\"\ntesting = false\n\
+             deployment = \"manual\"\n",
+            name
+        ))
+        .unwrap();
+        let matches = new_command().get_matches_from(["new"]);
+        match validate_spec(spec, &matches) {
+            Ok(config) => {
+                assert!(is_valid_project_name(&config.project_name));
+                Vec::new()
+            }
+            Err(errors) => errors,
+        }
+    }
+
+    #[test]
+    fn project_name_is_validated_once_sanitized() {
+        assert!(project_name_errors("my-shop").is_empty());
+        assert!(project_name_errors(" boutique ").is_empty());
+        assert_eq!(project_name_errors("---").len(), 1);
+        assert_eq!(project_name_errors("_").len(), 1);
+        assert_eq!(
+            project_name_errors("  "),
+            ["champ `project_name` manquant".to_string()]
+        );
     }
 }
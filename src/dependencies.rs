@@ -0,0 +1,31 @@
+//! Versions of the Python packages added to generated projects, in one place.
+//!
+//! Every range accepts the releases from the oldest tested minor version up to, but excluding,
+//! the next major version, so that `pip install` only brings compatible updates. Django itself is
+//! pinned to a feature release by `DjangoRelease::requirement`, and `--lock` records the exact
+//! versions installed in `requirements.lock`.
+
+// Pilotes de base de données
+pub const PSYCOPG: &str = "psycopg[binary]>=3.1,<4.0";
+pub const MYSQLCLIENT: &str = "mysqlclient>=2.2,<3.0";
+
+// Clients de cache
+pub const REDIS: &str = "redis>=5.0,<6.0";
+pub const PYMEMCACHE: &str = "pymemcache>=4.0,<5.0";
+
+// Configuration par variables d'environnement
+pub const DJANGO_ENVIRON: &str = "django-environ>=0.11,<1.0";
+
+// Fonctionnalités
+pub const DJANGO_REST_FRAMEWORK: &str = "djangorestframework>=3.14,<4.0";
+pub const GRAPHENE_DJANGO: &str = "graphene-django>=3.0,<4.0";
+pub const DJANGO_CORS_HEADERS: &str = "django-cors-headers>=4.0,<5.0";
+pub const STRIPE: &str = "stripe>=10.0,<11.0";
+
+// Outils de développement
+pub const PYTEST: &str = "pytest>=8.0,<9.0";
+pub const PYTEST_DJANGO: &str = "pytest-django>=4.8,<5.0";
+pub const COVERAGE: &str = "coverage>=7.4,<8.0";
+pub const RUFF: &str = "ruff>=0.5,<1.0";
+pub const MYPY: &str = "mypy>=1.10,<2.0";
+pub const DJANGO_STUBS: &str = "django-stubs>=4.2,<6.0";
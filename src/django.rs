@@ -1,254 +1,1723 @@
-use crate::config::ProjectConfig;
-use crate::utils::install::install_django;
+use crate::config::{
+    CacheBackend, DbType, Deployment, Feature, FeatureOptions, FrontendType, PackageManager,
+    ProjectConfig,
+};
+use crate::dependencies;
+use crate::dry_run;
+use crate::error::Result;
+use crate::project::ProjectLayout;
+use crate::settings::{Position, SettingsFile};
+use crate::templates::render;
+use crate::utils::check::{PythonVersion, NPM};
+use crate::utils::files::{create_dir, path_exists, read_file, remove_file, write_file};
+use crate::utils::install::{install_django, DjangoRelease};
+use crate::utils::process::{command_output, run_command};
+use crate::verify;
+use minijinja::context;
+use serde::Serialize;
+use std::env;
+use std::ffi::OsString;
 use std::fs;
-use std::process::Command as ShellCommand;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command as ShellCommand};
 
-pub fn create_django_project(project_name: &str) {
+/// Installs the Django release in the interpreter of the layout, then runs `django-admin startproject`
+/// in its existing root directory.
+pub fn create_django_project(layout: &ProjectLayout, django: &DjangoRelease) -> Result<()> {
     println!("Checking Django installation...");
 
-    // Ensure Django is installed, installing Python if necessary
-    install_django();
+    // Ensure Django is installed in the project interpreter
+    install_django(&layout.python, django)?;
+    dry_run::set_django_version(&django.to_string());
 
     println!("Creating Django project...");
 
     // Create the Django project using the installed django-admin
-    ShellCommand::new("django-admin")
-        .arg("startproject")
-        .arg(project_name)
-        .status()
-        .expect("Failed to create Django project");
+    run_command(
+        layout
+            .python
+            .django_admin()
+            .arg("startproject")
+            .arg(&layout.package)
+            .arg(&layout.root),
+    )?;
 
-    println!("Django project '{}' created successfully.", project_name);
+    println!("Django project '{}' created successfully.", layout.package);
+    Ok(())
 }
 
-pub fn configure_django_project(config: &ProjectConfig) {
+pub fn configure_django_project(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    django: &DjangoRelease,
+    python: PythonVersion,
+) -> Result<()> {
     println!("Configuration du projet...");
 
-    // Créer les applications Django nécessaires en fonction de la configuration
-    create_required_apps(config);
+    // Générer le fichier requirements.txt avec les dépendances de base
+    verify::step("requirements.txt");
+    generate_requirements_txt(layout, config, django)?;
+
+    // Ignorer .env, l'environnement virtuel et les fichiers générés
+    verify::step(".gitignore");
+    generate_gitignore(layout, config)?;
+
+    // Remplacer settings.py par un package settings/ si demandé
+    if config.split_settings {
+        verify::step("découpage des settings");
+        split_settings(layout)?;
+    }
 
     // Configurer le fichier settings.py
-    configure_settings_py(config);
+    verify::step("settings (environnement, base de données)");
+    configure_settings_py(layout, config)?;
+
+    // Appliquer les fonctionnalités choisies (applications, settings, dépendances)
+    let options = config.feature_options();
+    for feature in config.features() {
+        verify::step(format!("fonctionnalité « {} »", feature));
+        apply_feature(layout, feature, &options)?;
+    }
+
+    // Configurer les templates Django s'ils sont requis
+    if config.frontend_type == Some(FrontendType::DjangoTemplate) {
+        verify::step("templates Django");
+        setup_django_template(layout)?;
+    }
 
-    // Configurer le frontend s'il est requis
-    configure_frontend(config);
+    // Générer les fichiers Docker si ce mode de déploiement est choisi
+    if config.deployment == Deployment::Docker {
+        verify::step("fichiers Docker");
+        generate_docker_files(layout, config, python)?;
+    }
 
-    // Générer le fichier requirements.txt dynamiquement
-    generate_requirements_txt(config);
+    // Décrire le projet, ses dépendances et ses outils pour le gestionnaire de paquets choisi
+    verify::step("manifestes du gestionnaire de paquets");
+    generate_manifests(layout, config, django)?;
 
     println!("Configuration du projet terminée.");
+    Ok(())
 }
 
-fn create_required_apps(config: &ProjectConfig) {
-    if config.auth_enabled == "Oui" {
-        create_django_app(&config.project_name, "authentication");
+/// Applies the generators of a single feature to a project.
+///
+/// Every step is idempotent: existing apps, settings blocks and requirements are left untouched,
+/// so applying the same feature twice does not duplicate anything.
+///
+/// # Arguments
+///
+/// * `layout` - The layout of the project to modify.
+/// * `feature` - The feature to apply.
+/// * `options` - Choices refining the generated code (e.g. the cache backend).
+pub fn apply_feature(
+    layout: &ProjectLayout,
+    feature: Feature,
+    options: &FeatureOptions,
+) -> Result<()> {
+    match feature {
+        Feature::Auth => setup_authentication(layout, options.email_login),
+        Feature::Rest => {
+            create_django_app(layout, "api")?;
+            add_to_settings_list(layout, "INSTALLED_APPS", "'rest_framework'", Position::Last)?;
+            add_requirements(layout, feature.requirement().as_slice())
+        }
+        Feature::Graphql => {
+            create_django_app(layout, "api")?;
+            add_to_settings_list(
+                layout,
+                "INSTALLED_APPS",
+                "'graphene_django'",
+                Position::Last,
+            )?;
+            add_requirements(layout, feature.requirement().as_slice())
+        }
+        Feature::Email => {
+            load_environ(layout)?;
+            append_settings_block(
+                layout,
+                "# Email service configuration",
+                &render("settings/email.py", context! {})?,
+            )?;
+            add_env_variables(
+                layout,
+                &[
+                    EnvVariable::new(
+                        "EMAIL_BACKEND",
+                        "django.core.mail.backends.smtp.EmailBackend",
+                    ),
+                    EnvVariable::new("EMAIL_HOST", "localhost"),
+                    EnvVariable::new("EMAIL_PORT", "25"),
+                    EnvVariable::new("EMAIL_HOST_USER", ""),
+                    EnvVariable::secret("EMAIL_HOST_PASSWORD", ""),
+                    EnvVariable::new("EMAIL_USE_TLS", "False"),
+                    EnvVariable::new("DEFAULT_FROM_EMAIL", "webmaster@localhost"),
+                ],
+            )
+        }
+        Feature::Cache => configure_cache(layout, options.cache_backend),
+        Feature::React => {
+            setup_react_frontend(layout)?;
+            add_requirements(layout, feature.requirement().as_slice())
+        }
+        Feature::Payment => {
+            load_environ(layout)?;
+            append_settings_block(
+                layout,
+                "# Payment configuration (Stripe)",
+                &render("settings/payment.py", context! {})?,
+            )?;
+            add_env_variables(
+                layout,
+                &[
+                    EnvVariable::new("STRIPE_PUBLIC_KEY", ""),
+                    EnvVariable::secret("STRIPE_SECRET_KEY", ""),
+                ],
+            )?;
+            add_requirements(layout, feature.requirement().as_slice())
+        }
     }
+}
+
+/// Installs every dependency of the project with its package manager: from `requirements.txt`
+/// with pip, from `pyproject.toml` or `Pipfile` with the other tools.
+pub fn install_dependencies(layout: &ProjectLayout) -> Result<()> {
+    println!("Installation des dépendances...");
+
+    // requirements-dev.txt inclut requirements.txt et ajoute les outils de développement
+    let requirements = if path_exists(&layout.dev_requirements_path()) {
+        "requirements-dev.txt"
+    } else {
+        "requirements.txt"
+    };
 
-    if config.api_type != "Aucune" {
-        create_django_app(&config.project_name, "api");
+    let (mut command, args): (ShellCommand, &[&str]) = match layout.package_manager {
+        PackageManager::Pip => (layout.python.pip_install(), &["-r", requirements]),
+        PackageManager::Uv => (package_manager_command(layout), &["sync"]),
+        PackageManager::Poetry => (package_manager_command(layout), &["install", "--no-root"]),
+        PackageManager::Pdm => (package_manager_command(layout), &["install"]),
+        PackageManager::Pipenv => (package_manager_command(layout), &["install", "--dev"]),
+    };
+    command.args(args).args(offline_args(layout));
+    run_command(command.current_dir(&layout.root))?;
+
+    println!("Dépendances installées avec succès.");
+    Ok(())
+}
+
+/// Writes `requirements.lock` with the exact version and hash of every package installed in the
+/// project environment, for reproducible installs with `pip install --require-hashes`.
+///
+/// The versions come from `pip freeze`; each package is then downloaded again (from the wheelhouse
+/// when offline) and hashed with `pip hash`, as the installed files no longer carry a hash.
+///
+/// # Arguments
+///
+/// * `layout` - The layout of a project whose dependencies are installed.
+///
+/// # Returns
+///
+/// * `Ok(())` once `requirements.lock` is written,
+/// * `Err(CliError::CommandFailed)` if pip fails to list, download or hash a package.
+///
+/// # Example
+///
+/// ```
+/// install_dependencies(&layout)?;
+/// lock_dependencies(&layout)?;
+/// ```
+pub fn lock_dependencies(layout: &ProjectLayout) -> Result<()> {
+    println!("Génération de requirements.lock...");
+
+    let pins = freeze_pins(&command_output(
+        layout
+            .python
+            .pip()
+            .args(["freeze", "--exclude-editable"])
+            .current_dir(&layout.root),
+    )?);
+
+    // En simulation, pip freeze n'a rien listé et rien n'est téléchargé
+    let hashes = if pins.is_empty() || dry_run::is_enabled() {
+        Vec::new()
+    } else {
+        hash_distributions(layout, &pins)?
+    };
+
+    let mut content = String::from(
+        "# Versions exactes installées dans l'environnement du projet (django_cli new --lock).\n\
+         # Installation : pip install --require-hashes -r requirements.lock\n\
+         # Les empreintes sont celles des fichiers de la plateforme et de la version de Python\n\
+         # utilisées à la génération : sur une autre plateforme, régénérez ce fichier.\n",
+    );
+    for pin in &pins {
+        let name = normalize_name(pin.split("==").next().unwrap_or_default());
+        content.push_str(pin);
+        for (_, hash) in hashes.iter().filter(|(package, _)| *package == name) {
+            content.push_str(&format!(" \\\n    --hash={}", hash));
+        }
+        content.push('\n');
     }
+    write_file(&layout.root.join("requirements.lock"), content)?;
+
+    println!("Fichier requirements.lock généré avec succès.");
+    Ok(())
+}
+
+/// Downloads the pinned distributions into a temporary directory and returns the
+/// `(normalized name, sha256:...)` of each file, as printed by `pip hash`.
+fn hash_distributions(layout: &ProjectLayout, pins: &[String]) -> Result<Vec<(String, String)>> {
+    let download_dir = env::temp_dir().join(format!("django_cli-lock-{}", process::id()));
+    let hashes = download_and_hash(layout, pins, &download_dir);
+
+    // Les fichiers téléchargés ne servent qu'au calcul des empreintes
+    let _ = fs::remove_dir_all(&download_dir);
+    hashes
 }
 
-fn configure_frontend(config: &ProjectConfig) {
-    if config.frontend_type == "React" {
-        setup_react_frontend(&config.project_name);
-    } else if config.frontend_type == "Django Template" {
-        setup_django_template(&config.project_name);
+fn download_and_hash(
+    layout: &ProjectLayout,
+    pins: &[String],
+    download_dir: &Path,
+) -> Result<Vec<(String, String)>> {
+    run_command(
+        layout
+            .python
+            .pip_download()
+            .arg("--no-deps")
+            .arg("--dest")
+            .arg(download_dir)
+            .args(pins)
+            .current_dir(&layout.root),
+    )?;
+
+    let files: Vec<PathBuf> = fs::read_dir(download_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    if files.is_empty() {
+        return Ok(Vec::new());
     }
+
+    let output = command_output(layout.python.pip().arg("hash").args(&files))?;
+    Ok(parse_pip_hash(&output))
 }
 
-pub fn install_dependencies(project_name: &str) {
-    println!("Installation des dépendances...");
+/// Pinned requirements (`name==version`) of the output of `pip freeze`. Packages installed in
+/// editable mode or from a URL have no version to pin.
+fn freeze_pins(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.contains("==") && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
 
-    let project_dir = project_name.to_string();
+/// `(normalized name, sha256:...)` pairs of the output of `pip hash`.
+fn parse_pip_hash(output: &str) -> Vec<(String, String)> {
+    // « /tmp/.../Django-5.2.7-py3-none-any.whl: » puis « --hash=sha256:... »
+    let mut hashes = Vec::new();
+    let mut package = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(hash) = line.strip_prefix("--hash=") {
+            if let Some(package) = &package {
+                hashes.push((String::clone(package), hash.to_string()));
+            }
+        } else if let Some(file) = line.strip_suffix(':') {
+            package = Path::new(file)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(distribution_name);
+        }
+    }
+    hashes
+}
+
+/// Normalized project name of a wheel (`Django-5.2.7-py3-none-any.whl`) or source archive
+/// (`django-5.2.7.tar.gz`).
+fn distribution_name(file: &str) -> Option<String> {
+    let name = if file.ends_with(".whl") {
+        file.split('-').next()?
+    } else {
+        let stem = file
+            .strip_suffix(".tar.gz")
+            .or_else(|| file.strip_suffix(".zip"))?;
+        stem.rsplit_once('-')?.0
+    };
+    Some(normalize_name(name))
+}
+
+/// Package name as compared by pip: lowercase, with runs of `-`, `_` and `.` as a single `-`.
+fn normalize_name(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .split(['-', '_', '.'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Installs requirements just added to `requirements.txt`. uv, Poetry and PDM record them in
+/// pyproject.toml with their `add` command, Pipenv in its Pipfile; for pip and Pipenv, they are
+/// added to pyproject.toml here.
+///
+/// # Arguments
+///
+/// * `layout` - The layout of the project.
+/// * `requirements` - The requirement lines added, e.g. `stripe>=7.0,<11.0`.
+pub fn add_dependencies(layout: &ProjectLayout, requirements: &[String]) -> Result<()> {
+    if matches!(
+        layout.package_manager,
+        PackageManager::Pip | PackageManager::Pipenv
+    ) {
+        add_pyproject_dependencies(layout, requirements)?;
+    }
 
-    ShellCommand::new("pip")
-        .arg("install")
-        .arg("-r")
-        .arg("requirements.txt")
-        .current_dir(&project_dir)
-        .status()
-        .expect("Failed to install dependencies");
+    let action = match layout.package_manager {
+        PackageManager::Pip => return install_dependencies(layout),
+        _ if requirements.is_empty() => return Ok(()),
+        PackageManager::Pipenv => "install",
+        PackageManager::Uv | PackageManager::Poetry | PackageManager::Pdm => "add",
+    };
+
+    println!("Installation des dépendances...");
+
+    run_command(
+        package_manager_command(layout)
+            .arg(action)
+            .args(requirements)
+            .args(offline_args(layout))
+            .current_dir(&layout.root),
+    )?;
 
     println!("Dépendances installées avec succès.");
+    Ok(())
+}
+
+/// Options making uv install from the wheelhouse only, in offline mode (`--wheelhouse`). pip gets
+/// its own with `PythonEnv::pip_install`; the other managers are refused offline by `new`.
+fn offline_args(layout: &ProjectLayout) -> Vec<OsString> {
+    match (layout.package_manager, layout.python.wheelhouse()) {
+        (PackageManager::Uv, Some(wheelhouse)) => vec![
+            "--offline".into(),
+            "--no-index".into(),
+            "--find-links".into(),
+            wheelhouse.into(),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Command of the project package manager, which must not be pip.
+fn package_manager_command(layout: &ProjectLayout) -> ShellCommand {
+    let program = layout
+        .package_manager
+        .program()
+        .expect("pip runs from the project interpreter");
+    layout.python.tool(program)
+}
+
+/// Creates an app with `startapp`, unless it exists, and adds it to INSTALLED_APPS.
+fn create_django_app(layout: &ProjectLayout, app_name: &str) -> Result<()> {
+    if path_exists(&layout.root.join(app_name)) {
+        println!("L'application Django {} existe déjà.", app_name);
+    } else {
+        println!("Création de l'application Django {}...", app_name);
+
+        run_command(
+            layout
+                .python
+                .python()
+                .arg("manage.py")
+                .arg("startapp")
+                .arg(app_name)
+                .current_dir(&layout.root),
+        )?;
+
+        println!("Application Django {} créée avec succès.", app_name);
+    }
+
+    add_to_settings_list(
+        layout,
+        "INSTALLED_APPS",
+        &format!("'{}'", app_name),
+        Position::Last,
+    )
 }
 
-fn create_django_app(project_name: &str, app_name: &str) {
-    println!("Création de l'application Django {}...", app_name);
+/// Creates the `authentication` app with a `CustomUser` model, its admin and forms, and points
+/// AUTH_USER_MODEL to it. With `email_login`, users are identified by a unique email address
+/// instead of a username.
+///
+/// Modules already edited by hand are kept: only the stubs written by `startapp` are replaced.
+fn setup_authentication(layout: &ProjectLayout, email_login: bool) -> Result<()> {
+    create_django_app(layout, "authentication")?;
 
-    let project_dir = project_name.to_string();
-    ShellCommand::new("python")
-        .arg("manage.py")
-        .arg("startapp")
-        .arg(app_name)
-        .current_dir(&project_dir)
-        .status()
-        .expect("Failed to create Django app");
+    let context = context! {
+        email_login => email_login,
+        login_field => if email_login { "email" } else { "username" },
+    };
+    let app_dir = layout.root.join("authentication");
+    for module in ["models.py", "forms.py", "admin.py"] {
+        let path = app_dir.join(module);
+        if path_exists(&path) && !is_startapp_stub(&read_file(&path)?) {
+            println!(
+                "authentication/{} a déjà été modifié, il est conservé.",
+                module
+            );
+            continue;
+        }
+        write_file(
+            &path,
+            render(&format!("authentication/{}", module), context.clone())?,
+        )?;
+    }
+
+    let mut settings = SettingsFile::open(&layout.settings_path())?;
+    settings.set("AUTH_USER_MODEL", "'authentication.CustomUser'");
+    settings.save()
+}
 
-    println!("Application Django {} créée avec succès.", app_name);
+/// Whether a module only holds the imports and comments written by `startapp`.
+fn is_startapp_stub(source: &str) -> bool {
+    source.lines().map(str::trim).all(|line| {
+        line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("import ")
+            || line.starts_with("from ")
+    })
 }
 
-fn configure_settings_py(config: &ProjectConfig) {
-    let settings_path = format!(
-        "{}/{}/settings.py",
-        config.project_name, config.project_name
+/// Generates the first migration of `app_name` with `makemigrations`, once Django is installed.
+pub fn make_migrations(layout: &ProjectLayout, app_name: &str) -> Result<()> {
+    println!("Génération des migrations de {}...", app_name);
+
+    run_command(
+        layout
+            .python
+            .python()
+            .arg("manage.py")
+            .arg("makemigrations")
+            .arg(app_name)
+            .current_dir(&layout.root),
+    )
+}
+
+/// Migrations of `app_label` already applied to the database of the project, as listed by
+/// `manage.py showmigrations`.
+pub fn applied_migrations(layout: &ProjectLayout, app_label: &str) -> Result<Vec<String>> {
+    let output = command_output(
+        layout
+            .python
+            .python()
+            .arg("manage.py")
+            .arg("showmigrations")
+            .arg(app_label)
+            .current_dir(&layout.root),
+    )?;
+    Ok(parse_showmigrations(&output))
+}
+
+/// Names of the applied migrations (`[X] 0001_initial`) of the output of `showmigrations`.
+fn parse_showmigrations(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("[X] "))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Replaces the `settings.py` of `startproject` with a `settings/` package: `base.py` keeps its
+/// content, `development.py`, `production.py` and `test.py` override it. `manage.py` defaults to
+/// the development settings, `wsgi.py` and `asgi.py` to the production ones; all of them keep
+/// reading `DJANGO_SETTINGS_MODULE` from the environment first.
+fn split_settings(layout: &ProjectLayout) -> Result<()> {
+    println!("Découpage des settings par environnement...");
+
+    let settings_py = layout.root.join(&layout.package).join("settings.py");
+    let settings = read_file(&settings_py)?;
+    let settings_dir = layout.settings_dir();
+    create_dir(&settings_dir)?;
+    write_file(&settings_dir.join("__init__.py"), "")?;
+
+    // base.py est un niveau plus bas que settings.py : BASE_DIR doit rester la racine du projet
+    write_file(
+        &settings_dir.join("base.py"),
+        settings.replace(
+            "Path(__file__).resolve().parent.parent",
+            "Path(__file__).resolve().parent.parent.parent",
+        ),
+    )?;
+    for module in ["development", "production", "test"] {
+        write_file(
+            &settings_dir.join(format!("{}.py", module)),
+            render(&format!("settings/{}.py", module), context! {})?,
+        )?;
+    }
+    remove_file(&settings_py)?;
+
+    set_default_settings_module(layout, &layout.root.join("manage.py"), "development")?;
+    for entry_point in ["wsgi.py", "asgi.py"] {
+        set_default_settings_module(
+            layout,
+            &layout.root.join(&layout.package).join(entry_point),
+            "production",
+        )?;
+    }
+
+    println!("Settings découpés dans {}.", settings_dir.display());
+    Ok(())
+}
+
+/// Points the `os.environ.setdefault('DJANGO_SETTINGS_MODULE', ...)` of a file to a module of the
+/// settings package.
+fn set_default_settings_module(layout: &ProjectLayout, path: &Path, module: &str) -> Result<()> {
+    let content = read_file(path)?;
+    let settings = format!("'{}.settings'", layout.package);
+    let replacement = format!("'{}.settings.{}'", layout.package, module);
+    write_file(path, content.replace(&settings, &replacement))
+}
+
+/// Reads SECRET_KEY, DEBUG, ALLOWED_HOSTS and DATABASES from the environment, and writes the
+/// values for this machine to `.env`, with a freshly generated SECRET_KEY.
+fn configure_settings_py(layout: &ProjectLayout, config: &ProjectConfig) -> Result<()> {
+    load_environ(layout)?;
+
+    // Les valeurs écrites par startproject ne doivent pas être versionnées
+    let mut settings = SettingsFile::open(&layout.settings_path())?;
+    settings.set("SECRET_KEY", "env('SECRET_KEY')");
+    settings.set("DEBUG", "env.bool('DEBUG', default=False)");
+    settings.set("ALLOWED_HOSTS", "env.list('ALLOWED_HOSTS', default=[])");
+    settings.save()?;
+    configure_database(layout, config)?;
+
+    let mut variables = vec![
+        EnvVariable::secret("SECRET_KEY", generate_secret_key(layout)?),
+        EnvVariable::new("DEBUG", "True"),
+        EnvVariable::new("ALLOWED_HOSTS", "localhost,127.0.0.1,[::1]"),
+    ];
+    variables.extend(database_url(config).map(|url| EnvVariable::new("DATABASE_URL", url)));
+    add_env_variables(layout, &variables)
+}
+
+/// Replaces the DATABASES of `startproject` with one read from `DATABASE_URL`, defaulting to the
+/// database chosen at generation time. SQLite keeps the `startproject` configuration.
+fn configure_database(layout: &ProjectLayout, config: &ProjectConfig) -> Result<()> {
+    let Some(url) = database_url(config) else {
+        return Ok(());
+    };
+
+    // Avec des settings découpés, SQLite reste la base de développement
+    let settings_path = if layout.has_split_settings() {
+        layout.settings_dir().join("production.py")
+    } else {
+        layout.settings_path()
+    };
+    let mut settings = SettingsFile::open(&settings_path)?;
+    settings.set(
+        "DATABASES",
+        render("settings/database.py", context! { url => url })?.trim_end(),
     );
-    let mut settings = fs::read_to_string(&settings_path).expect("Failed to read settings.py");
+    settings.save()
+}
+
+/// URL of the database chosen at generation time, `None` for SQLite whose file stays next to
+/// manage.py. The password is left out: it belongs to `.env` only.
+fn database_url(config: &ProjectConfig) -> Option<String> {
+    let db_type = config.db_type;
+    let scheme = match db_type {
+        DbType::Sqlite => return None,
+        DbType::Postgresql => "postgres",
+        DbType::Mysql => "mysql",
+    };
+
+    Some(format!(
+        "{}://{}@{}:{}/{}",
+        scheme,
+        db_type.default_user(),
+        config.db_host.as_deref().unwrap_or("localhost"),
+        config.db_port.or(db_type.default_port())?,
+        config.db_name.as_deref().unwrap_or(&config.project_name),
+    ))
+}
+
+/// A new SECRET_KEY. `secrets.token_urlsafe` only uses characters that need no quoting in `.env`.
+fn generate_secret_key(layout: &ProjectLayout) -> Result<String> {
+    let key = command_output(
+        layout
+            .python
+            .python()
+            .arg("-c")
+            .arg("import secrets; print(secrets.token_urlsafe(50))"),
+    )?;
+
+    // En simulation, la commande n'est pas exécutée
+    if key.trim().is_empty() {
+        return Ok("<clé générée>".to_string());
+    }
+    Ok(key.trim().to_string())
+}
+
+/// Defines `env`, the django-environ reader of the environment and of `.env`, right after
+/// BASE_DIR in settings.py, and adds django-environ to the requirements. Does nothing if the
+/// settings already define it.
+fn load_environ(layout: &ProjectLayout) -> Result<()> {
+    add_requirements(layout, &[dependencies::DJANGO_ENVIRON])?;
+
+    let mut settings = SettingsFile::open(&layout.settings_path())?;
+    if settings.source().contains("environ.Env(") {
+        return Ok(());
+    }
+
+    settings.add_import("import environ");
+    settings.insert_after("BASE_DIR", &render("settings/environ.py", context! {})?);
+    settings.save()
+}
 
-    if config.db_type != "SQLite" {
-        settings.push_str("\n# Database configuration\nDATABASES = {...}");
+/// A variable of the generated `.env`, with the value shown in `.env.example`.
+struct EnvVariable {
+    name: &'static str,
+    value: String,
+    example: String,
+}
+
+impl EnvVariable {
+    /// A variable whose value can be committed: `.env.example` shows it too.
+    fn new(name: &'static str, value: impl Into<String>) -> EnvVariable {
+        let value = value.into();
+        EnvVariable {
+            name,
+            example: value.clone(),
+            value,
+        }
     }
 
-    if config.auth_enabled == "Oui" {
-        settings.push_str("\n# Authentication settings\nINSTALLED_APPS += ['authentication']");
+    /// A secret, left empty in `.env.example`.
+    fn secret(name: &'static str, value: impl Into<String>) -> EnvVariable {
+        EnvVariable {
+            name,
+            value: value.into(),
+            example: String::new(),
+        }
     }
+}
+
+/// Adds variables to `.env` and `.env.example`, skipping the ones already defined, so that
+/// features added later extend both files without overwriting local values.
+fn add_env_variables(layout: &ProjectLayout, variables: &[EnvVariable]) -> Result<()> {
+    for (file, header, example) in [
+        (
+            ".env",
+            "# Local configuration read by the settings. Never commit this file.\n",
+            false,
+        ),
+        (
+            ".env.example",
+            "# Variables read by the settings: copy this file to .env and fill in the values.\n",
+            true,
+        ),
+    ] {
+        let path = layout.root.join(file);
+        let mut content = if path_exists(&path) {
+            read_file(&path)?
+        } else {
+            header.to_string()
+        };
 
-    if config.email_service == "Oui" {
-        settings.push_str("\n# Email service configuration\nEMAIL_BACKEND = 'django.core.mail.backends.smtp.EmailBackend'");
+        let defined: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, _)| name.trim())
+            .collect();
+        let missing: Vec<&EnvVariable> = variables
+            .iter()
+            .filter(|variable| !defined.contains(&variable.name))
+            .collect();
+
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for variable in missing {
+            let value = if example {
+                &variable.example
+            } else {
+                &variable.value
+            };
+            content.push_str(&format!("{}={}\n", variable.name, value));
+        }
+
+        write_file(&path, content)?;
     }
 
-    if config.cache_system == "Oui" {
-        settings.push_str("\n# Cache settings\nCACHES = {...}");
+    ignore_env_file(layout)
+}
+
+/// Writes `.gitignore`. An existing file is kept, and only completed with `.env`.
+fn generate_gitignore(layout: &ProjectLayout, config: &ProjectConfig) -> Result<()> {
+    if path_exists(&layout.root.join(".gitignore")) {
+        return ignore_env_file(layout);
     }
 
-    fs::write(&settings_path, settings).expect("Failed to write settings.py");
+    write_file(
+        &layout.root.join(".gitignore"),
+        render(
+            "project/gitignore",
+            context! {
+                testing => config.testing,
+                linting => config.linting,
+                react => config.frontend_type == Some(FrontendType::React),
+            },
+        )?,
+    )
 }
 
-fn setup_react_frontend(project_name: &str) {
-    println!(
-        "Configuration du frontend React pour le projet {}...",
-        project_name
-    );
+/// Adds `.env` to `.gitignore`, creating it if needed, so that secrets are never committed.
+fn ignore_env_file(layout: &ProjectLayout) -> Result<()> {
+    let path = layout.root.join(".gitignore");
+    let mut gitignore = if path_exists(&path) {
+        read_file(&path)?
+    } else {
+        String::new()
+    };
 
-    let frontend_dir = format!("{}/frontend", project_name);
+    if gitignore
+        .lines()
+        .any(|line| matches!(line.trim(), ".env" | "/.env"))
+    {
+        return Ok(());
+    }
 
-    #[cfg(windows)]
-    pub const NPM: &str = "npm.cmd";
+    if !gitignore.is_empty() && !gitignore.ends_with('\n') {
+        gitignore.push('\n');
+    }
+    gitignore.push_str(".env\n");
+    write_file(&path, gitignore)
+}
 
-    #[cfg(not(windows))]
-    pub const NPM: &str = "npm";
+fn configure_cache(layout: &ProjectLayout, cache_backend: CacheBackend) -> Result<()> {
+    let Some(settings) = cache_settings(layout, cache_backend)? else {
+        println!("Aucun système de cache sélectionné.");
+        return Ok(());
+    };
 
-    // Étape 1 : Initialiser le projet React avec create-react-app
-    let status = ShellCommand::new(NPM)
-        .arg("init")
-        .arg("react-app")
-        .arg(&frontend_dir)
-        .status()
-        .expect("Failed to create React app");
+    // Les adresses des serveurs de cache viennent de l'environnement
+    let variable = match cache_backend {
+        CacheBackend::Redis => Some(EnvVariable::new("REDIS_URL", "redis://127.0.0.1:6379/1")),
+        CacheBackend::Memcached => Some(EnvVariable::new("MEMCACHED_LOCATION", "127.0.0.1:11211")),
+        _ => None,
+    };
+    if let Some(variable) = variable {
+        load_environ(layout)?;
+        add_env_variables(layout, &[variable])?;
+    }
+
+    append_settings_block(layout, "# Cache settings", &settings)?;
+
+    if let Some(client) = cache_backend.client_requirement() {
+        add_requirements(layout, &[client])?;
+    }
+
+    if cache_backend == CacheBackend::Database {
+        println!("Pensez à créer la table du cache : python manage.py createcachetable");
+    }
+
+    Ok(())
+}
+
+/// CACHES block for the backend, `None` when no cache is configured. Server locations are read
+/// from `REDIS_URL` or `MEMCACHED_LOCATION`, falling back to a local server.
+fn cache_settings(layout: &ProjectLayout, cache_backend: CacheBackend) -> Result<Option<String>> {
+    let Some(backend) = cache_backend.backend() else {
+        return Ok(None);
+    };
+    render(
+        "settings/cache.py",
+        context! {
+            cache_backend => cache_backend,
+            backend => backend,
+            package => layout.package,
+        },
+    )
+    .map(Some)
+}
 
-    if !status.success() {
-        panic!("Failed to initialize React frontend");
+/// Adds an entry to a list of settings.py, e.g. an app to INSTALLED_APPS, unless it is already listed.
+fn add_to_settings_list(
+    layout: &ProjectLayout,
+    name: &str,
+    entry: &str,
+    position: Position,
+) -> Result<()> {
+    let mut settings = SettingsFile::open(&layout.settings_path())?;
+    if !settings.add_to_list(name, entry, position)? {
+        println!(
+            "{} contient déjà {} dans {}.",
+            settings.file_name(),
+            entry,
+            name
+        );
+        return Ok(());
     }
+    settings.save()
+}
 
-    // Étape 2 : Installer django-cors-headers pour gérer les CORS
-    let project_dir = project_name.to_string();
-    ShellCommand::new("pip")
-        .arg("install")
-        .arg("django-cors-headers")
-        .current_dir(&project_dir)
-        .status()
-        .expect("Failed to install django-cors-headers");
+/// Appends a block to settings.py, headed by `header`, unless a block with that header already exists.
+fn append_settings_block(layout: &ProjectLayout, header: &str, body: &str) -> Result<()> {
+    append_settings_block_to(&layout.settings_path(), header, body)
+}
 
-    // Étape 3 : Configurer django-cors-headers dans settings.py
-    let settings_path = format!("{}/{}/settings.py", project_name, project_name);
-    let mut settings = fs::read_to_string(&settings_path).expect("Failed to read settings.py");
+/// Appends a block to a settings module, unless a block with that header already exists.
+fn append_settings_block_to(settings_path: &Path, header: &str, body: &str) -> Result<()> {
+    let mut settings = read_file(settings_path)?;
 
-    settings.push_str("\n# CORS configuration\n");
-    settings.push_str("INSTALLED_APPS += ['corsheaders']\n");
-    settings.push_str("MIDDLEWARE = ['corsheaders.middleware.CorsMiddleware'] + MIDDLEWARE\n");
-    settings.push_str("CORS_ORIGIN_ALLOW_ALL = True\n");
+    if settings.lines().any(|line| line.trim() == header) {
+        println!(
+            "{} contient déjà la section « {} ».",
+            settings_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+            header
+        );
+        return Ok(());
+    }
 
-    // Configurer les fichiers statiques
-    settings.push_str("\n# Static files (CSS, JavaScript, Images)\n");
-    settings.push_str("STATICFILES_DIRS = [os.path.join(BASE_DIR, 'frontend/build/static')]\n");
+    if !settings.ends_with('\n') {
+        settings.push('\n');
+    }
+    settings.push('\n');
+    settings.push_str(header);
+    settings.push('\n');
+    settings.push_str(body);
 
-    fs::write(&settings_path, settings).expect("Failed to write settings.py");
+    write_file(settings_path, settings)
+}
+
+fn setup_react_frontend(layout: &ProjectLayout) -> Result<()> {
+    println!(
+        "Configuration du frontend React pour le projet {}...",
+        layout.package
+    );
+
+    let frontend_dir = layout.root.join("frontend");
+
+    // Étape 1 : Initialiser le projet React avec create-react-app
+    if path_exists(&frontend_dir) {
+        println!("Le dossier frontend existe déjà.");
+    } else {
+        run_command(
+            ShellCommand::new(NPM)
+                .arg("init")
+                .arg("react-app")
+                .arg(&frontend_dir),
+        )?;
+    }
+
+    // Étape 2 : Configurer django-cors-headers et les fichiers statiques dans settings.py ;
+    // CorsMiddleware doit précéder les middlewares qui produisent des réponses. Le paquet est
+    // déclaré dans les dépendances et installé par le gestionnaire de paquets du projet
+    add_to_settings_list(layout, "INSTALLED_APPS", "'corsheaders'", Position::Last)?;
+    add_to_settings_list(
+        layout,
+        "MIDDLEWARE",
+        "'corsheaders.middleware.CorsMiddleware'",
+        Position::First,
+    )?;
+    add_to_settings_list(
+        layout,
+        "STATICFILES_DIRS",
+        "BASE_DIR / 'frontend' / 'build' / 'static'",
+        Position::Last,
+    )?;
+    append_settings_block(
+        layout,
+        "# CORS configuration",
+        &render("settings/cors.py", context! {})?,
+    )?;
 
     println!(
         "Frontend React configuré avec succès pour le projet {}.",
-        project_name
+        layout.package
     );
+    Ok(())
 }
 
-fn setup_django_template(project_name: &str) {
+fn setup_django_template(layout: &ProjectLayout) -> Result<()> {
     println!(
         "Configuration des templates Django pour le projet {}...",
-        project_name
+        layout.package
     );
 
-    let templates_dir = format!("{}/templates", project_name);
-    fs::create_dir_all(&templates_dir).expect("Failed to create templates directory");
+    let templates_dir = layout.root.join("templates");
+    create_dir(&templates_dir)?;
 
     // Étape 1 : Mettre à jour settings.py pour inclure le dossier templates
-    let settings_path = format!("{}/{}/settings.py", project_name, project_name);
-    let mut settings = fs::read_to_string(&settings_path).expect("Failed to read settings.py");
-
-    settings.push_str("\n# Template configuration\n");
-    settings.push_str("TEMPLATES[0]['DIRS'] = [os.path.join(BASE_DIR, 'templates')]\n");
-
-    fs::write(&settings_path, settings).expect("Failed to write settings.py");
+    let mut settings = SettingsFile::open(&layout.settings_path())?;
+    if settings.add_template_dir("BASE_DIR / 'templates'")? {
+        settings.save()?;
+    } else {
+        println!(
+            "{} contient déjà le dossier templates.",
+            settings.file_name()
+        );
+    }
 
     // Étape 2 : Créer un fichier base.html dans le dossier templates
-    let base_html_path = format!("{}/base.html", templates_dir);
-    let base_html_content = r#"
-<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{% block title %}My Django Project{% endblock %}</title>
-    <link rel="stylesheet" href="{% static 'styles.css' %}">
-</head>
-<body>
-    <header>
-        <h1>Welcome to My Django Project</h1>
-    </header>
-    <main>
-        {% block content %}
-        <p>Hello, world!</p>
-        {% endblock %}
-    </main>
-    <footer>
-        <p>&copy; 2023 My Django Project</p>
-    </footer>
-</body>
-</html>
-"#;
-    fs::write(base_html_path, base_html_content).expect("Failed to create base.html");
+    let base_html_path = templates_dir.join("base.html");
+    if path_exists(&base_html_path) {
+        println!("Le fichier base.html existe déjà.");
+        return Ok(());
+    }
+
+    let base_html_content = render(
+        "project/base.html",
+        context! { project_name => layout.package },
+    )?;
+    write_file(&base_html_path, base_html_content)?;
 
     println!(
         "Templates Django configurés avec succès pour le projet {}.",
-        project_name
+        layout.package
     );
+    Ok(())
 }
 
-fn generate_requirements_txt(config: &ProjectConfig) {
+fn generate_requirements_txt(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    django: &DjangoRelease,
+) -> Result<()> {
     println!("Génération du fichier requirements.txt...");
 
-    let mut requirements = String::new();
+    // Ajouter Django par défaut, les fonctionnalités ajoutent ensuite leurs propres dépendances
+    add_requirements(layout, &[&django.requirement()])?;
+
+    // Ajouter le pilote de la base de données choisie
+    if let Some(driver) = config.db_type.driver_requirement() {
+        add_requirements(layout, &[driver])?;
+    }
+
+    println!("Fichier requirements.txt généré avec succès.");
+    Ok(())
+}
 
-    // Ajouter Django par défaut
-    requirements.push_str("Django>=4.0,<5.0\n");
+/// Adds requirement lines to requirements.txt, skipping packages that are already listed.
+fn add_requirements(layout: &ProjectLayout, lines: &[&str]) -> Result<()> {
+    let requirements_path = layout.requirements_path();
+    let mut requirements = if path_exists(&requirements_path) {
+        read_file(&requirements_path)?
+    } else {
+        String::new()
+    };
 
-    // Ajouter djangorestframework si une API est requise
-    if config.api_type == "REST" {
-        requirements.push_str("djangorestframework>=3.13.0,<4.0\n");
+    let listed: Vec<String> = requirements.lines().map(requirement_name).collect();
+
+    for line in lines {
+        if listed.contains(&requirement_name(line)) {
+            continue;
+        }
+        if !requirements.is_empty() && !requirements.ends_with('\n') {
+            requirements.push('\n');
+        }
+        requirements.push_str(line);
+        requirements.push('\n');
     }
 
-    // Ajouter graphene-django si GraphQL est requis
-    if config.api_type == "GraphQL" {
-        requirements.push_str("graphene-django>=2.15.0,<3.0\n");
+    write_file(&requirements_path, requirements)
+}
+
+/// Requirement lines of requirements.txt, without comments and blank lines.
+pub fn read_requirements(layout: &ProjectLayout) -> Result<Vec<String>> {
+    let requirements_path = layout.requirements_path();
+    if !path_exists(&requirements_path) {
+        return Ok(Vec::new());
     }
 
-    // Ajouter des dépendances pour React si nécessaire
-    if config.frontend_type == "React" {
-        // Par exemple, ajouter django-cors-headers pour gérer les requêtes CORS avec React
-        requirements.push_str("django-cors-headers>=3.5.0,<4.0\n");
+    Ok(read_file(&requirements_path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Writes pyproject.toml with the same dependencies as requirements.txt, and the files listing
+/// the development tools: `requirements-dev.txt` for pip, `Pipfile` for Pipenv.
+fn generate_manifests(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    django: &DjangoRelease,
+) -> Result<()> {
+    let requirements = read_requirements(layout)?;
+    let dev_requirements = config.dev_requirements();
+
+    generate_pyproject(layout, config, django, &requirements, &dev_requirements)?;
+
+    match layout.package_manager {
+        PackageManager::Pip if !dev_requirements.is_empty() => {
+            let mut content = String::from("-r requirements.txt\n");
+            for requirement in &dev_requirements {
+                content.push_str(requirement);
+                content.push('\n');
+            }
+            write_file(&layout.dev_requirements_path(), content)
+        }
+        PackageManager::Pipenv => generate_pipfile(layout, &requirements, &dev_requirements),
+        _ => Ok(()),
     }
+}
 
-    // Écrire le fichier requirements.txt
-    let requirements_path = format!("{}/requirements.txt", config.project_name);
-    fs::write(requirements_path, requirements).expect("Failed to create requirements.txt");
+/// Writes pyproject.toml: project metadata, dependencies, development tools in the `dev`
+/// dependency group, and the configuration of pytest, coverage, ruff and mypy when selected.
+fn generate_pyproject(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    django: &DjangoRelease,
+    requirements: &[String],
+    dev_requirements: &[&str],
+) -> Result<()> {
+    println!("Génération du fichier pyproject.toml...");
 
-    println!("Fichier requirements.txt généré avec succès.");
+    let (major, minor) = django.min_python;
+    let content = render(
+        "project/pyproject.toml",
+        context! {
+            name => config.project_name,
+            requires_python => format!(">={}.{}", major, minor),
+            python_version => format!("{}.{}", major, minor),
+            python_target => format!("py{}{}", major, minor),
+            dependencies => requirements,
+            dev_dependencies => dev_requirements,
+            settings_module => if layout.has_split_settings() {
+                format!("{}.settings.test", layout.package)
+            } else {
+                format!("{}.settings", layout.package)
+            },
+            package_manager => layout.package_manager,
+            testing => config.testing,
+            linting => config.linting,
+        },
+    )?;
+    write_file(&layout.root.join("pyproject.toml"), content)?;
+
+    println!("Fichier pyproject.toml généré avec succès.");
+    Ok(())
+}
+
+/// Writes the Pipfile of a Pipenv project, with the development tools as `dev-packages`.
+fn generate_pipfile(
+    layout: &ProjectLayout,
+    requirements: &[String],
+    dev_requirements: &[&str],
+) -> Result<()> {
+    println!("Génération du fichier Pipfile...");
+
+    let packages = pipfile_packages(requirements.iter().map(String::as_str));
+    let dev_packages = pipfile_packages(dev_requirements.iter().copied());
+
+    write_file(
+        &layout.root.join("Pipfile"),
+        render("project/Pipfile", context! { packages, dev_packages })?,
+    )?;
+
+    println!("Fichier Pipfile généré avec succès.");
+    Ok(())
+}
+
+/// Entries of a Pipfile section, one per requirement line.
+fn pipfile_packages<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Requirement> {
+    lines.map(Requirement::parse).collect()
+}
+
+/// Adds requirements to the `dependencies` array of pyproject.toml, for the package managers
+/// that do not maintain it themselves. Nothing is done without a pyproject.toml.
+fn add_pyproject_dependencies(layout: &ProjectLayout, requirements: &[String]) -> Result<()> {
+    let pyproject_path = layout.root.join("pyproject.toml");
+    if requirements.is_empty() || !path_exists(&pyproject_path) {
+        return Ok(());
+    }
+
+    match insert_pyproject_dependencies(&read_file(&pyproject_path)?, requirements) {
+        Some(content) => write_file(&pyproject_path, content),
+        None => Ok(()),
+    }
+}
+
+/// Adds the requirements that are not listed yet to the `dependencies` array of a pyproject.toml,
+/// or returns `None` if it has no such array.
+fn insert_pyproject_dependencies(pyproject: &str, requirements: &[String]) -> Option<String> {
+    let mut lines: Vec<String> = pyproject.lines().map(str::to_string).collect();
+
+    // Le tableau est celui généré par le template : une dépendance par ligne, puis `]`
+    let start = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("dependencies = ["))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim() == "]")
+        .map(|offset| start + offset)?;
+
+    let listed: Vec<String> = lines[start + 1..end]
+        .iter()
+        .map(|line| requirement_name(line.trim().trim_matches(|c| c == '"' || c == ',')))
+        .collect();
+    // Les marqueurs acceptent les deux types de guillemets : les apostrophes évitent l'échappement
+    let added: Vec<String> = requirements
+        .iter()
+        .filter(|requirement| !listed.contains(&requirement_name(requirement)))
+        .map(|requirement| format!("    \"{}\",", requirement.replace('"', "'")))
+        .collect();
+    lines.splice(end..end, added);
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    Some(content)
+}
+
+/// A requirement line split for the Pipfile: `psycopg[binary]>=3.1; python_version < "3.13"`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct Requirement {
+    name: String,
+    extras: Vec<String>,
+    /// Version specifier, `*` when absent.
+    version: String,
+    /// Environment markers, with single quotes so they fit in a TOML string.
+    markers: Option<String>,
+}
+
+impl Requirement {
+    /// Parses a requirement line
+    /// (`psycopg[binary]>=3.1` -> `psycopg`, `["binary"]`, `>=3.1`, no markers).
+    fn parse(line: &str) -> Requirement {
+        let (line, markers) = match line.split_once(';') {
+            Some((line, markers)) => (line, Some(markers.trim().replace('"', "'"))),
+            None => (line, None),
+        };
+        let line = line.trim();
+        let name_end = line
+            .find(|c: char| "<>=!~[ ".contains(c))
+            .unwrap_or(line.len());
+        let (name, rest) = line.split_at(name_end);
+        let rest = rest.trim_start();
+
+        let (extras, version) = match rest.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+            Some((extras, version)) => (
+                extras
+                    .split(',')
+                    .map(|extra| extra.trim().to_string())
+                    .filter(|extra| !extra.is_empty())
+                    .collect(),
+                version,
+            ),
+            None => (Vec::new(), rest),
+        };
+
+        let version = match version.trim() {
+            "" => "*".to_string(),
+            version => version.to_string(),
+        };
+        Requirement {
+            name: name.to_string(),
+            extras,
+            version,
+            markers: markers.filter(|markers| !markers.is_empty()),
+        }
+    }
+}
+
+/// Normalized package name of a requirement line (`Django>=4.0` -> `django`).
+fn requirement_name(line: &str) -> String {
+    normalize_name(
+        line.trim()
+            .split(|c: char| "<>=!~;[ ".contains(c))
+            .next()
+            .unwrap_or(""),
+    )
+}
+
+fn generate_docker_files(
+    layout: &ProjectLayout,
+    config: &ProjectConfig,
+    python: PythonVersion,
+) -> Result<()> {
+    println!("Génération des fichiers Docker...");
+
+    // L'image utilise la version de Python du projet, que la version de Django supporte
+    let context = context! {
+        project => config,
+        python_version => format!("{}.{}", python.major, python.minor),
+        db_name => config.db_name.as_deref().unwrap_or(&config.project_name),
+    };
+
+    write_file(
+        &layout.root.join("Dockerfile"),
+        render("project/Dockerfile", &context)?,
+    )?;
+
+    write_file(
+        &layout.root.join("docker-compose.yml"),
+        render("project/docker-compose.yml", &context)?,
+    )?;
+
+    write_file(
+        &layout.root.join(".dockerignore"),
+        render("project/dockerignore", &context)?,
+    )?;
+
+    println!("Fichiers Docker générés avec succès.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::utils::install::DJANGO_RELEASES;
+    use clap::ValueEnum;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn database_url_uses_the_engine_defaults() {
+        assert_eq!(database_url(&test_config("")), None);
+        assert_eq!(
+            database_url(&test_config("db_type = \"postgresql\"")).as_deref(),
+            Some("postgres://postgres@localhost:5432/shop")
+        );
+    }
+
+    #[test]
+    fn database_url_uses_the_chosen_connection() {
+        let config = test_config(
+            "db_type = \"mysql\"\ndb_host = \"db.internal\"\ndb_port = 3307\ndb_name = \"store\"",
+        );
+        let url = database_url(&config);
+        assert_eq!(url.as_deref(), Some("mysql://root@db.internal:3307/store"));
+    }
+
+    #[test]
+    fn databases_replace_the_startproject_configuration() {
+        let layout = startproject_fixture("databases");
+        let startproject = std::fs::read_to_string(layout.settings_path()).unwrap();
+        configure_database(&layout, &test_config("")).unwrap();
+        let sqlite = std::fs::read_to_string(layout.settings_path()).unwrap();
+        configure_database(&layout, &test_config("db_type = \"postgresql\"")).unwrap();
+        let postgresql = std::fs::read_to_string(layout.settings_path()).unwrap();
+        std::fs::remove_dir_all(&layout.root).unwrap();
+
+        assert_eq!(sqlite, startproject);
+        assert_eq!(postgresql.matches("DATABASES = ").count(), 1);
+        assert!(postgresql.contains(
+            "DATABASES = {\n    'default': env.db('DATABASE_URL', default='postgres://postgres@localhost:5432/shop'),\n}\n"
+        ));
+        assert!(!postgresql.contains("db.sqlite3"));
+    }
+
+    #[test]
+    fn cache_settings_match_the_backend() {
+        let layout = ProjectLayout::new("shop", "shop");
+        let cases = [
+            (
+                CacheBackend::Redis,
+                "django.core.cache.backends.redis.RedisCache",
+                "env('REDIS_URL', default='redis://127.0.0.1:6379/1')",
+            ),
+            (
+                CacheBackend::Memcached,
+                "django.core.cache.backends.memcached.PyMemcacheCache",
+                "env('MEMCACHED_LOCATION', default='127.0.0.1:11211')",
+            ),
+            (
+                CacheBackend::Locmem,
+                "django.core.cache.backends.locmem.LocMemCache",
+                "'shop'",
+            ),
+            (
+                CacheBackend::Database,
+                "django.core.cache.backends.db.DatabaseCache",
+                "'django_cache'",
+            ),
+        ];
+        for (cache_backend, backend, location) in cases {
+            let settings = cache_settings(&layout, cache_backend).unwrap().unwrap();
+            assert!(
+                settings.contains(&format!("'BACKEND': '{}',", backend)),
+                "{}",
+                settings
+            );
+            assert!(
+                settings.contains(&format!("'LOCATION': {},", location)),
+                "{}",
+                settings
+            );
+        }
+        assert_eq!(cache_settings(&layout, CacheBackend::None).unwrap(), None);
+    }
+
+    #[test]
+    fn pyproject_describes_the_project_and_its_tools() {
+        let dir = std::env::temp_dir().join(format!("django_cli-pyproject-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = test_config("testing = true\nlinting = true\npackage_manager = \"uv\"");
+        let layout =
+            ProjectLayout::new(&dir, &config.project_name).with_package_manager(PackageManager::Uv);
+        let django = DJANGO_RELEASES
+            .iter()
+            .find(|release| release.version == (4, 2))
+            .unwrap();
+        generate_pyproject(
+            &layout,
+            &config,
+            django,
+            &["Django>=4.2,<5.0".to_string()],
+            &["pytest-django>=4.8"],
+        )
+        .unwrap();
+        add_pyproject_dependencies(
+            &layout,
+            &["django>=5.0".to_string(), "stripe>=7.0,<11.0".to_string()],
+        )
+        .unwrap();
+        let pyproject = std::fs::read_to_string(dir.join("pyproject.toml")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let pyproject: toml::Table = toml::from_str(&pyproject).unwrap();
+        assert_eq!(pyproject["project"]["name"].as_str(), Some("shop"));
+        assert_eq!(
+            pyproject["project"]["requires-python"].as_str(),
+            Some(">=3.8")
+        );
+        assert_eq!(
+            pyproject["project"]["dependencies"],
+            toml::Value::from(vec!["Django>=4.2,<5.0", "stripe>=7.0,<11.0"])
+        );
+        assert_eq!(
+            pyproject["dependency-groups"]["dev"],
+            toml::Value::from(vec!["pytest-django>=4.8"])
+        );
+        assert_eq!(pyproject["tool"]["uv"]["package"].as_bool(), Some(false));
+        assert!(pyproject["tool"].get("poetry").is_none());
+        assert_eq!(
+            pyproject["tool"]["pytest"]["ini_options"]["DJANGO_SETTINGS_MODULE"].as_str(),
+            Some("shop.settings")
+        );
+        assert_eq!(
+            pyproject["tool"]["ruff"]["target-version"].as_str(),
+            Some("py38")
+        );
+    }
+
+    /// Writes the files of `django-admin startproject shop` to an empty temporary directory.
+    fn startproject_fixture(test: &str) -> ProjectLayout {
+        let root = std::env::temp_dir().join(format!("django_cli-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("shop")).unwrap();
+
+        let mut env = minijinja::Environment::new();
+        env.set_keep_trailing_newline(true);
+        let context = context! {
+            project_name => "shop",
+            secret_key => "django-insecure-test",
+            django_version => "5.2",
+            docs_version => "5.2",
+        };
+        for (path, source) in [
+            (
+                "manage.py",
+                include_str!("../templates/startproject/manage.py.j2"),
+            ),
+            (
+                "shop/settings.py",
+                include_str!("../templates/startproject/settings.py.j2"),
+            ),
+            (
+                "shop/urls.py",
+                include_str!("../templates/startproject/urls.py.j2"),
+            ),
+            (
+                "shop/wsgi.py",
+                include_str!("../templates/startproject/wsgi.py.j2"),
+            ),
+            (
+                "shop/asgi.py",
+                include_str!("../templates/startproject/asgi.py.j2"),
+            ),
+        ] {
+            let content = env.render_str(source, &context).unwrap();
+            std::fs::write(root.join(path), content).unwrap();
+        }
+        std::fs::write(root.join("shop/__init__.py"), "").unwrap();
+        ProjectLayout::new(&root, "shop")
+    }
+
+    #[test]
+    fn split_settings_keep_base_dir_at_the_project_root() {
+        let layout = startproject_fixture("split-settings");
+        split_settings(&layout).unwrap();
+
+        let base_py = layout.settings_dir().join("base.py");
+        let base = std::fs::read_to_string(&base_py).unwrap();
+        let read = |path: &str| std::fs::read_to_string(layout.root.join(path)).unwrap();
+        let (manage, wsgi, asgi) = (
+            read("manage.py"),
+            read("shop/wsgi.py"),
+            read("shop/asgi.py"),
+        );
+        let settings_py_left = layout.root.join("shop/settings.py").exists();
+        std::fs::remove_dir_all(&layout.root).unwrap();
+
+        // base.py est dans shop/settings/ : trois parents ramènent à la racine du projet
+        assert!(base.contains("BASE_DIR = Path(__file__).resolve().parent.parent.parent\n"));
+        assert_eq!(base_py.ancestors().nth(3), Some(layout.root.as_path()));
+        assert!(!settings_py_left);
+        assert!(manage.contains("'shop.settings.development'"));
+        assert!(wsgi.contains("'shop.settings.production'"));
+        assert!(asgi.contains("'shop.settings.production'"));
+    }
+
+    #[test]
+    fn distribution_names() {
+        let cases = [
+            ("Django-5.2.7-py3-none-any.whl", Some("django")),
+            (
+                "django_environ-0.11.2-py2.py3-none-any.whl",
+                Some("django-environ"),
+            ),
+            // Wheel avec une étiquette de build
+            (
+                "psycopg_binary-3.2.3-1-cp312-cp312-manylinux_2_17_x86_64.whl",
+                Some("psycopg-binary"),
+            ),
+            ("django-5.2.7.tar.gz", Some("django")),
+            (
+                "django-cors-headers-4.4.0.tar.gz",
+                Some("django-cors-headers"),
+            ),
+            ("zope.interface-7.1.1.tar.gz", Some("zope-interface")),
+            ("Zope__Interface-7.1.1.zip", Some("zope-interface")),
+            ("mysqlclient-2.2.4.tar.bz2", None),
+            ("README", None),
+        ];
+        for (file, expected) in cases {
+            assert_eq!(distribution_name(file).as_deref(), expected, "{}", file);
+        }
+    }
+
+    #[test]
+    fn pins_of_pip_freeze() {
+        let output = "asgiref==3.8.1\n\
+                      # Editable install with no version control (demo==0.1.0)\n\
+                      Django==5.2.7\n\
+                      mylib @ file:///tmp/mylib\n\
+                      \n";
+        assert_eq!(freeze_pins(output), ["asgiref==3.8.1", "Django==5.2.7"]);
+    }
+
+    #[test]
+    fn hashes_of_pip_hash() {
+        let output = "/tmp/lock/Django-5.2.7-py3-none-any.whl:\n\
+                      --hash=sha256:aaaa\n\
+                      /tmp/lock/django_cors_headers-4.4.0.tar.gz:\n\
+                      --hash=sha256:bbbb\n\
+                      /tmp/lock/notes.txt:\n\
+                      --hash=sha256:cccc\n";
+        assert_eq!(
+            parse_pip_hash(output),
+            [
+                ("django".to_string(), "sha256:aaaa".to_string()),
+                ("django-cors-headers".to_string(), "sha256:bbbb".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parsed_requirements() {
+        let requirement =
+            |name: &str, extras: &[&str], version: &str, markers: Option<&str>| Requirement {
+                name: name.to_string(),
+                extras: extras.iter().map(|extra| extra.to_string()).collect(),
+                version: version.to_string(),
+                markers: markers.map(str::to_string),
+            };
+        let cases = [
+            (
+                "Django>=5.2,<5.3",
+                requirement("Django", &[], ">=5.2,<5.3", None),
+            ),
+            ("gunicorn", requirement("gunicorn", &[], "*", None)),
+            (
+                "psycopg[binary]>=3.1",
+                requirement("psycopg", &["binary"], ">=3.1", None),
+            ),
+            (
+                "celery[redis, msgpack] ~= 5.4",
+                requirement("celery", &["redis", "msgpack"], "~= 5.4", None),
+            ),
+            (
+                "tomli>=2.0; python_version < \"3.11\"",
+                requirement("tomli", &[], ">=2.0", Some("python_version < '3.11'")),
+            ),
+            (
+                "pywin32 ; sys_platform == 'win32'",
+                requirement("pywin32", &[], "*", Some("sys_platform == 'win32'")),
+            ),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(Requirement::parse(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn requirement_names() {
+        let cases = [
+            ("Django>=5.2,<5.3", "django"),
+            ("django_cors_headers>=4.0", "django-cors-headers"),
+            ("psycopg[binary]>=3.1", "psycopg"),
+            ("zope.interface ; python_version >= '3.9'", "zope-interface"),
+            ("  ruff", "ruff"),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(requirement_name(line), expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn pyproject_dependencies_are_added_once() {
+        let pyproject = "[project]\n\
+                         name = \"demo\"\n\
+                         dependencies = [\n    \"Django>=5.2,<5.3\",\n    \"psycopg[binary]>=3.1\",\n]\n\
+                         \n[dependency-groups]\ndev = [\n    \"pytest\",\n]\n";
+        let requirements = [
+            "django>=5.2,<5.3".to_string(),
+            "psycopg>=3.2".to_string(),
+            "stripe>=7.0,<11.0".to_string(),
+            "tomli>=2.0; python_version < \"3.11\"".to_string(),
+        ];
+        assert_eq!(
+            insert_pyproject_dependencies(pyproject, &requirements).unwrap(),
+            "[project]\n\
+             name = \"demo\"\n\
+             dependencies = [\n    \"Django>=5.2,<5.3\",\n    \"psycopg[binary]>=3.1\",\n    \
+             \"stripe>=7.0,<11.0\",\n    \"tomli>=2.0; python_version < '3.11'\",\n]\n\
+             \n[dependency-groups]\ndev = [\n    \"pytest\",\n]\n"
+        );
+        assert_eq!(
+            insert_pyproject_dependencies("[project]\nname = \"demo\"\n", &requirements),
+            None
+        );
+    }
+
+    #[test]
+    fn applied_migrations_are_read_from_showmigrations() {
+        let output = "auth\n [X] 0001_initial\n [X] 0002_alter_permission_name_max_length\n [ ] 0003_alter_user_email_max_length\n";
+        assert_eq!(
+            parse_showmigrations(output),
+            ["0001_initial", "0002_alter_permission_name_max_length"]
+        );
+        assert!(parse_showmigrations("auth\n [ ] 0001_initial\n").is_empty());
+        assert!(parse_showmigrations("auth\n (no migrations)\n").is_empty());
+    }
+
+    #[test]
+    fn normalized_names() {
+        for name in [
+            "Django-Environ",
+            "django_environ",
+            "django.environ",
+            "django--environ",
+        ] {
+            assert_eq!(normalize_name(name), "django-environ", "{}", name);
+        }
+    }
+
+    /// Content of every file under `root`, by path relative to it.
+    fn tree(root: &Path) -> BTreeMap<PathBuf, String> {
+        let mut files = BTreeMap::new();
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else {
+                    let content = std::fs::read_to_string(&path).unwrap();
+                    files.insert(path.strip_prefix(root).unwrap().to_path_buf(), content);
+                }
+            }
+        }
+        files
+    }
+
+    #[test]
+    fn applying_features_twice_changes_nothing() {
+        let layout = startproject_fixture("idempotency");
+        let root = &layout.root;
+        // Les applications et le frontend existent déjà : ni startapp ni npm ne sont lancés
+        for app in ["authentication", "api"] {
+            std::fs::create_dir(root.join(app)).unwrap();
+            std::fs::write(root.join(app).join("__init__.py"), "").unwrap();
+            std::fs::write(
+                root.join(app).join("models.py"),
+                "from django.db import models\n\n# Create your models here.\n",
+            )
+            .unwrap();
+            std::fs::write(
+                root.join(app).join("admin.py"),
+                "from django.contrib import admin\n\n# Register your models here.\n",
+            )
+            .unwrap();
+        }
+        std::fs::create_dir(root.join("frontend")).unwrap();
+        std::fs::write(root.join("requirements.txt"), "Django>=5.2,<5.3\n").unwrap();
+
+        let options = FeatureOptions {
+            cache_backend: CacheBackend::Redis,
+            email_login: true,
+        };
+        let apply_all = || {
+            for &feature in Feature::value_variants() {
+                apply_feature(&layout, feature, &options).unwrap();
+            }
+        };
+        apply_all();
+        let once = tree(root);
+        apply_all();
+        let twice = tree(root);
+        std::fs::remove_dir_all(root).unwrap();
+
+        let settings = &once[Path::new("shop/settings.py")];
+        assert!(settings.contains("AUTH_USER_MODEL = 'authentication.CustomUser'"));
+        assert!(settings.contains("# Cache settings"));
+        assert!(once[Path::new("requirements.txt")].contains("stripe"));
+        assert!(once.contains_key(Path::new(".env")));
+        assert_eq!(
+            once.keys().collect::<Vec<_>>(),
+            twice.keys().collect::<Vec<_>>()
+        );
+        for (path, content) in &once {
+            assert_eq!(content, &twice[path], "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn docker_compose_reads_the_secrets_from_the_env_file() {
+        let config = test_config(
+            "db_type = \"postgresql\"\ncache_backend = \"redis\"\nsplit_settings = true\ndeployment = \"docker\"",
+        );
+        let compose = render(
+            "project/docker-compose.yml",
+            context! { project => config, python_version => "3.12", db_name => "shop" },
+        )
+        .unwrap();
+
+        assert!(compose.contains("    env_file: .env\n"));
+        assert!(compose.contains("      DJANGO_SETTINGS_MODULE: shop.settings.production\n"));
+        assert!(compose.contains("      DATABASE_URL: postgres://postgres:postgres@db:5432/shop\n"));
+        assert!(compose.contains("      REDIS_URL: redis://redis:6379/1\n"));
+        for literal in ["SECRET_KEY", "DEBUG", "ALLOWED_HOSTS"] {
+            assert!(!compose.contains(&format!("{}:", literal)), "{}", literal);
+        }
+    }
 }
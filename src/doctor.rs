@@ -0,0 +1,807 @@
+use crate::error::{CliError, Result};
+use crate::project::ProjectLayout;
+use crate::utils::check::{
+    get_python_version, get_tool_output, parse_tool_version, short_version, PythonVersion, NPM,
+};
+use crate::utils::install::{DjangoRelease, PYTHON_DOWNLOAD_URL};
+use crate::utils::interpreters::discover_interpreters;
+use crate::utils::venv::PythonEnv;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command as ShellCommand;
+
+/// Oldest Node.js major version supported by the React tooling.
+const MIN_NODE: u32 = 18;
+
+/// Output of `django_cli doctor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned table for a terminal.
+    Table,
+    /// JSON document for scripts and CI.
+    Json,
+}
+
+/// Outcome of a check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the report: `id` is stable for scripts, `name` and `detail` are for humans.
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+#[derive(Serialize)]
+struct Summary {
+    pass: usize,
+    warn: usize,
+    fail: usize,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    checks: &'a [Check],
+    summary: Summary,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Status::Pass => "OK",
+            Status::Warn => "ATTENTION",
+            Status::Fail => "ÉCHEC",
+        })
+    }
+}
+
+impl Check {
+    pub fn new(
+        id: &'static str,
+        name: &'static str,
+        status: Status,
+        detail: impl Into<String>,
+    ) -> Check {
+        Check {
+            id,
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Checks the development tools against the Django compatibility matrix and, when `path`
+/// contains a Django project, its deployment settings, migrations and environment variables.
+///
+/// # Arguments
+///
+/// * `path` - The directory to inspect, usually the current one.
+/// * `format` - Whether to print a table or a JSON document.
+///
+/// # Returns
+///
+/// * `Ok(())` if no check failed (warnings are allowed),
+/// * `Err(CliError::ChecksFailed)` with the number of failed checks otherwise.
+///
+/// # Example
+///
+/// ```
+/// run_doctor(Path::new("."), OutputFormat::Json)?;
+/// ```
+pub fn run_doctor(path: &Path, format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Table {
+        println!("Vérification de l'environnement...");
+    }
+
+    let checks = collect_checks(path);
+    match format {
+        OutputFormat::Table => print_table(&checks),
+        OutputFormat::Json => print_json(&checks),
+    }
+
+    let failed = count(&checks, Status::Fail);
+    if failed > 0 {
+        return Err(CliError::ChecksFailed(failed));
+    }
+    Ok(())
+}
+
+fn collect_checks(path: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+    let layout = ProjectLayout::detect(path);
+
+    // L'environnement virtuel du projet en priorité, sinon le premier interpréteur trouvé
+    let python = match PythonEnv::detect(path) {
+        python if python.venv_path().is_some() => Some(python),
+        _ => discover_interpreters()
+            .into_iter()
+            .next()
+            .map(|interpreter| PythonEnv::system(interpreter.path)),
+    };
+    let python_version = python
+        .as_ref()
+        .and_then(|python| get_python_version(python.executable()));
+
+    let django = match (&python, python_version) {
+        (Some(python), Some(version)) => {
+            checks.push(check_python(python, version));
+            checks.push(check_pip(python));
+            checks.push(check_venv(python, layout.as_ref()));
+            let (check, django) = check_django(python, version, layout.is_some());
+            checks.push(check);
+            django
+        }
+        _ => {
+            checks.push(Check::new(
+                "python",
+                "Python",
+                Status::Fail,
+                format!("introuvable, installez Python 3 : {}", PYTHON_DOWNLOAD_URL),
+            ));
+            None
+        }
+    };
+
+    let frontend = layout
+        .as_ref()
+        .is_some_and(|layout| layout.root.join("frontend").is_dir());
+    checks.push(check_node(frontend));
+    checks.push(check_tool(
+        "npm",
+        "npm",
+        ShellCommand::new(NPM).arg("--version"),
+        frontend,
+        "nécessaire pour le frontend React",
+    ));
+    let docker = layout
+        .as_ref()
+        .is_some_and(|layout| layout.root.join("Dockerfile").is_file());
+    checks.push(check_tool(
+        "docker",
+        "Docker",
+        ShellCommand::new("docker").arg("--version"),
+        false,
+        if docker {
+            "le projet contient un Dockerfile"
+        } else {
+            "nécessaire pour --deployment docker"
+        },
+    ));
+
+    // Dans un projet, seul le client de sa base de données compte
+    let engine = layout
+        .as_ref()
+        .map(|layout| database_engine(&read_settings(layout, &["development", "production"])));
+    let reference = django.or_else(|| python_version.and_then(DjangoRelease::newest_for));
+    for client in ["postgresql", "mysql", "sqlite3"] {
+        if engine.is_some_and(|engine| engine != client) {
+            continue;
+        }
+        if let Some(check) = check_database_client(client, reference, engine.is_some()) {
+            checks.push(check);
+        }
+    }
+
+    let Some(layout) = layout else {
+        checks.push(Check::new(
+            "project",
+            "Projet Django",
+            Status::Warn,
+            format!(
+                "aucun projet dans {}, vérifications du projet ignorées",
+                path.display()
+            ),
+        ));
+        return checks;
+    };
+
+    checks.push(Check::new(
+        "project",
+        "Projet Django",
+        Status::Pass,
+        format!("'{}' dans {}", layout.package, layout.root.display()),
+    ));
+    // manage.py ne peut pas se lancer sans Django
+    if let Some(python) = python.filter(|python| python.has_module("django")) {
+        let layout = layout.with_python(python);
+        checks.push(check_deploy(&layout));
+        checks.push(check_migrations(&layout));
+        checks.push(check_environment(&layout));
+        return checks;
+    }
+    checks.push(check_environment(&layout));
+
+    checks
+}
+
+fn check_python(python: &PythonEnv, version: PythonVersion) -> Check {
+    let executable = python.executable().display();
+    match DjangoRelease::newest_for(version) {
+        Some(django) => Check::new(
+            "python",
+            "Python",
+            Status::Pass,
+            format!("{} ({}), Django {} possible", version, executable, django),
+        ),
+        None => Check::new(
+            "python",
+            "Python",
+            Status::Fail,
+            format!(
+                "{} ({}) n'est pris en charge par aucune version de Django",
+                version, executable
+            ),
+        ),
+    }
+}
+
+fn check_pip(python: &PythonEnv) -> Check {
+    let output = python
+        .pip()
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+
+    match output.and_then(|output| parse_tool_version(&String::from_utf8_lossy(&output.stdout))) {
+        Some(version) => Check::new("pip", "pip", Status::Pass, version),
+        None => Check::new(
+            "pip",
+            "pip",
+            Status::Fail,
+            "introuvable, installez-le avec `python -m ensurepip --upgrade`",
+        ),
+    }
+}
+
+fn check_venv(python: &PythonEnv, layout: Option<&ProjectLayout>) -> Check {
+    if let Some(venv) = python.venv_path() {
+        return Check::new("venv", "venv", Status::Pass, venv.display().to_string());
+    }
+    // Debian et Ubuntu livrent venv sans ensurepip (paquet python3-venv)
+    if !python.has_module("venv") || !python.has_module("ensurepip") {
+        return Check::new(
+            "venv",
+            "venv",
+            Status::Fail,
+            "module venv indisponible, installez python3-venv",
+        );
+    }
+    match layout {
+        Some(_) => Check::new(
+            "venv",
+            "venv",
+            Status::Warn,
+            "le projet n'a pas d'environnement virtuel (.venv)",
+        ),
+        None => Check::new("venv", "venv", Status::Pass, "disponible"),
+    }
+}
+
+/// Checks the installed Django against the interpreter, and returns its release if known.
+fn check_django(
+    python: &PythonEnv,
+    python_version: PythonVersion,
+    in_project: bool,
+) -> (Check, Option<&'static DjangoRelease>) {
+    let output = python
+        .python()
+        .arg("-c")
+        .arg("import django; print(django.get_version())")
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    let Some(version) =
+        output.map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    else {
+        let status = if in_project {
+            Status::Fail
+        } else {
+            Status::Warn
+        };
+        return (
+            Check::new(
+                "django",
+                "Django",
+                status,
+                "non installé dans cet environnement",
+            ),
+            None,
+        );
+    };
+
+    let release = short_version(&version)
+        .and_then(|(major, minor)| DjangoRelease::find(&format!("{}.{}", major, minor)));
+    let check = match release {
+        None => Check::new(
+            "django",
+            "Django",
+            Status::Warn,
+            format!("{} absent de la matrice de compatibilité", version),
+        ),
+        Some(release) if !release.supports(python_version) => Check::new(
+            "django",
+            "Django",
+            Status::Fail,
+            format!(
+                "{} ne prend pas en charge Python {} (Python {})",
+                version,
+                python_version,
+                release.python_range()
+            ),
+        ),
+        Some(release) => Check::new(
+            "django",
+            "Django",
+            Status::Pass,
+            format!("{}{}", version, if release.lts { " LTS" } else { "" }),
+        ),
+    };
+    (check, release)
+}
+
+fn check_node(required: bool) -> Check {
+    let Some(version) = get_tool_output(ShellCommand::new("node").arg("--version"))
+        .as_deref()
+        .and_then(parse_tool_version)
+    else {
+        return missing(
+            "node",
+            "Node.js",
+            required,
+            "nécessaire pour le frontend React",
+        );
+    };
+
+    match short_version(&version) {
+        Some((major, _)) if major < MIN_NODE => Check::new(
+            "node",
+            "Node.js",
+            if required { Status::Fail } else { Status::Warn },
+            format!("{}, Node.js {} ou plus récent requis", version, MIN_NODE),
+        ),
+        _ => Check::new("node", "Node.js", Status::Pass, version),
+    }
+}
+
+fn check_tool(
+    id: &'static str,
+    name: &'static str,
+    command: &mut ShellCommand,
+    required: bool,
+    purpose: &str,
+) -> Check {
+    match get_tool_output(command)
+        .as_deref()
+        .and_then(parse_tool_version)
+    {
+        Some(version) => Check::new(id, name, Status::Pass, version),
+        None => missing(id, name, required, purpose),
+    }
+}
+
+fn missing(id: &'static str, name: &'static str, required: bool, purpose: &str) -> Check {
+    Check::new(
+        id,
+        name,
+        if required { Status::Fail } else { Status::Warn },
+        format!("introuvable ({})", purpose),
+    )
+}
+
+/// Checks the client of a database against the oldest server supported by `django`.
+/// Outside a project, clients that are not installed are not reported.
+fn check_database_client(
+    engine: &str,
+    django: Option<&DjangoRelease>,
+    in_project: bool,
+) -> Option<Check> {
+    let (id, name, program) = match engine {
+        "postgresql" => ("postgresql", "Client PostgreSQL", "psql"),
+        "mysql" => ("mysql", "Client MySQL", "mysql"),
+        _ => ("sqlite", "Client SQLite", "sqlite3"),
+    };
+
+    let Some(output) = get_tool_output(ShellCommand::new(program).arg("--version")) else {
+        return in_project.then(|| {
+            missing(
+                id,
+                name,
+                false,
+                &format!("`{}` est utile pour inspecter la base", program),
+            )
+        });
+    };
+    let version = parse_tool_version(&output).unwrap_or_default();
+
+    // Les versions de MariaDB ne suivent pas celles de MySQL
+    let minimum = match (engine, django) {
+        (_, _) if output.contains("MariaDB") => None,
+        ("postgresql", Some(django)) => Some((django.min_postgresql, "PostgreSQL")),
+        ("mysql", Some(django)) => Some((django.min_mysql, "MySQL")),
+        _ => None,
+    };
+    let check = match (minimum, short_version(&version)) {
+        (Some((minimum, server)), Some(current)) if current < minimum => Check::new(
+            id,
+            name,
+            Status::Warn,
+            format!(
+                "{}, Django {} requiert {} {}.{} ou plus récent",
+                version,
+                django.map(ToString::to_string).unwrap_or_default(),
+                server,
+                minimum.0,
+                minimum.1
+            ),
+        ),
+        _ => Check::new(id, name, Status::Pass, format!("{} {}", program, version)),
+    };
+    Some(check)
+}
+
+/// Database engine configured in `settings.py`, by its backend or by the default
+/// `DATABASE_URL`: `postgresql`, `mysql` or `sqlite3`.
+fn database_engine(settings: &str) -> &'static str {
+    if settings.contains("django.db.backends.postgresql") || settings.contains("'postgres://") {
+        "postgresql"
+    } else if settings.contains("django.db.backends.mysql") || settings.contains("'mysql://") {
+        "mysql"
+    } else {
+        "sqlite3"
+    }
+}
+
+/// Runs `manage.py check --deploy` and reports the number of issues it found.
+fn check_deploy(layout: &ProjectLayout) -> Check {
+    let output = layout
+        .python
+        .python()
+        .args(["manage.py", "check", "--deploy"])
+        .current_dir(&layout.root)
+        .output();
+    let Ok(output) = output else {
+        return Check::new(
+            "deploy",
+            "check --deploy",
+            Status::Fail,
+            "impossible de lancer manage.py",
+        );
+    };
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    if !output.status.success() {
+        return Check::new("deploy", "check --deploy", Status::Fail, last_line(&text));
+    }
+    // « System check identified some issues: ... System check identified 6 issues (0 silenced). »
+    let issues = text
+        .split("System check identified ")
+        .skip(1)
+        .find_map(|rest| rest.split_whitespace().next()?.parse::<usize>().ok())
+        .unwrap_or(0);
+    if issues == 0 {
+        Check::new("deploy", "check --deploy", Status::Pass, "aucun problème")
+    } else {
+        Check::new(
+            "deploy",
+            "check --deploy",
+            Status::Warn,
+            format!(
+                "{} avertissement(s), voir `python manage.py check --deploy`",
+                issues
+            ),
+        )
+    }
+}
+
+/// Looks for model changes without migration, then for migrations not applied to the database.
+fn check_migrations(layout: &ProjectLayout) -> Check {
+    let manage = |args: &[&str]| {
+        layout
+            .python
+            .python()
+            .arg("manage.py")
+            .args(args)
+            .current_dir(&layout.root)
+            .output()
+            .ok()
+    };
+
+    match manage(&["makemigrations", "--check", "--dry-run"]) {
+        Some(output) if output.status.success() => {}
+        Some(output) if output.status.code() == Some(1) && output.stderr.is_empty() => {
+            return Check::new(
+                "migrations",
+                "Migrations",
+                Status::Warn,
+                "modèles modifiés sans migration, lancez `python manage.py makemigrations`",
+            );
+        }
+        output => {
+            return Check::new(
+                "migrations",
+                "Migrations",
+                Status::Fail,
+                output
+                    .map(|output| last_line(&String::from_utf8_lossy(&output.stderr)))
+                    .unwrap_or_else(|| "impossible de lancer manage.py".to_string()),
+            );
+        }
+    }
+
+    // migrate --check sort en erreur sans rien afficher s'il reste des migrations à appliquer
+    match manage(&["migrate", "--check"]) {
+        Some(output) if output.status.success() => {
+            Check::new("migrations", "Migrations", Status::Pass, "à jour")
+        }
+        Some(output) if output.stderr.is_empty() => Check::new(
+            "migrations",
+            "Migrations",
+            Status::Warn,
+            "migrations non appliquées, lancez `python manage.py migrate`",
+        ),
+        output => Check::new(
+            "migrations",
+            "Migrations",
+            Status::Warn,
+            format!(
+                "base de données inaccessible : {}",
+                output
+                    .map(|output| last_line(&String::from_utf8_lossy(&output.stderr)))
+                    .unwrap_or_default()
+            ),
+        ),
+    }
+}
+
+/// Checks that the environment variables read by the settings are set, in the environment or
+/// in the project `.env`. Variables without a default value are required.
+fn check_environment(layout: &ProjectLayout) -> Check {
+    // Modules chargés par manage.py : le module de DJANGO_SETTINGS_MODULE, development par défaut
+    let module = env::var("DJANGO_SETTINGS_MODULE").unwrap_or_default();
+    let module = module
+        .strip_prefix(&format!("{}.settings.", layout.package))
+        .unwrap_or("development");
+    let settings = read_settings(layout, &[module]);
+    let dotenv = fs::read_to_string(layout.root.join(".env")).unwrap_or_default();
+    let is_set = |name: &str| {
+        env::var_os(name).is_some()
+            || dotenv
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .any(|(key, _)| key.trim().trim_start_matches("export ").trim() == name)
+    };
+
+    let (mut required, mut optional) = (Vec::new(), Vec::new());
+    for (name, has_default) in settings_variables(&settings) {
+        if is_set(&name) {
+            continue;
+        }
+        if has_default {
+            optional.push(name);
+        } else {
+            required.push(name);
+        }
+    }
+
+    if !required.is_empty() {
+        Check::new(
+            "environment",
+            "Variables d'environnement",
+            Status::Fail,
+            format!("manquantes : {}", required.join(", ")),
+        )
+    } else if !optional.is_empty() {
+        Check::new(
+            "environment",
+            "Variables d'environnement",
+            Status::Warn,
+            format!(
+                "non définies, valeurs par défaut utilisées : {}",
+                optional.join(", ")
+            ),
+        )
+    } else {
+        Check::new(
+            "environment",
+            "Variables d'environnement",
+            Status::Pass,
+            "toutes définies",
+        )
+    }
+}
+
+/// Source of the project settings: `settings.py`, or `base.py` followed by the given modules
+/// when the settings are split by environment.
+fn read_settings(layout: &ProjectLayout, modules: &[&str]) -> String {
+    let mut settings = fs::read_to_string(layout.settings_path()).unwrap_or_default();
+    if layout.has_split_settings() {
+        for module in modules {
+            let path = layout.settings_dir().join(format!("{}.py", module));
+            settings.push_str(&fs::read_to_string(path).unwrap_or_default());
+        }
+    }
+    settings
+}
+
+/// Environment variables read by settings, with whether a default value is given:
+/// `os.environ.get('X', ...)`, `os.getenv('X')`, `env('X', default=...)` have one,
+/// `os.environ['X']` and `env('X')` do not.
+fn settings_variables(settings: &str) -> Vec<(String, bool)> {
+    let mut variables: Vec<(String, bool)> = Vec::new();
+    let patterns = [
+        ("os.environ.get(", true),
+        ("os.getenv(", true),
+        ("os.environ[", false),
+        ("env(", false),
+        ("env.str(", false),
+        ("env.bool(", false),
+        ("env.int(", false),
+        ("env.list(", false),
+        ("env.db(", false),
+    ];
+
+    for line in settings
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+    {
+        for (pattern, optional) in patterns {
+            for (index, _) in line.match_indices(pattern) {
+                // `getenv(` et `os.environ(` contiennent aussi `env(`
+                let before = line[..index].chars().last();
+                if before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+                    continue;
+                }
+                let rest = line[index + pattern.len()..].trim_start();
+                let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+                    continue;
+                };
+                let Some((name, after)) = rest[1..].split_once(quote) else {
+                    continue;
+                };
+                let has_default = optional || after.trim_start().starts_with(',');
+                if !variables.iter().any(|(known, _)| known == name) {
+                    variables.push((name.to_string(), has_default));
+                }
+            }
+        }
+    }
+
+    variables
+}
+
+fn last_line(text: &str) -> String {
+    text.lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("erreur inconnue")
+        .to_string()
+}
+
+fn count(checks: &[Check], status: Status) -> usize {
+    checks.iter().filter(|check| check.status == status).count()
+}
+
+pub fn print_table(checks: &[Check]) {
+    let status_width = checks
+        .iter()
+        .map(|check| check.status.to_string().chars().count())
+        .chain(["Statut".len()])
+        .max()
+        .unwrap_or_default();
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.chars().count())
+        .chain(["Vérification".chars().count()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:<status_width$}  {:<name_width$}  Détail",
+        "Statut", "Vérification"
+    );
+    for check in checks {
+        println!(
+            "{:<status_width$}  {:<name_width$}  {}",
+            check.status.to_string(),
+            check.name,
+            check.detail
+        );
+    }
+    println!(
+        "{} OK, {} avertissement(s), {} échec(s)",
+        count(checks, Status::Pass),
+        count(checks, Status::Warn),
+        count(checks, Status::Fail)
+    );
+}
+
+fn print_json(checks: &[Check]) {
+    println!("{}", json_report(checks));
+}
+
+/// The JSON document of `--format json`: the checks, then the number of checks per status.
+fn json_report(checks: &[Check]) -> String {
+    let report = Report {
+        checks,
+        summary: Summary {
+            pass: count(checks, Status::Pass),
+            warn: count(checks, Status::Warn),
+            fail: count(checks, Status::Fail),
+        },
+    };
+    serde_json::to_string_pretty(&report).expect("the report is serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn settings_variables_tell_required_from_optional() {
+        let settings = "\
+import os
+SECRET_KEY = os.environ['SECRET_KEY']
+DEBUG = os.environ.get('DEBUG', 'False') == 'True'
+ALLOWED_HOSTS = os.getenv(\"ALLOWED_HOSTS\", '').split(',')
+# STRIPE_KEY = os.environ['STRIPE_KEY']
+DATABASES = {'default': env.db('DATABASE_URL')}
+EMAIL_HOST = env('EMAIL_HOST', default='localhost')
+EMAIL_PORT = env.int('EMAIL_PORT')
+SENTRY_DSN = os.environ.get('SECRET_KEY')
+";
+        assert_eq!(
+            settings_variables(settings),
+            [
+                ("SECRET_KEY".to_string(), false),
+                ("DEBUG".to_string(), true),
+                ("ALLOWED_HOSTS".to_string(), true),
+                ("DATABASE_URL".to_string(), false),
+                ("EMAIL_HOST".to_string(), true),
+                ("EMAIL_PORT".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_report_lists_checks_and_counts() {
+        let checks = [
+            Check::new("python", "Python", Status::Pass, "Python 3.12.1"),
+            Check::new("node", "Node.js", Status::Warn, "introuvable"),
+            Check::new("django", "Django", Status::Fail, "non installé"),
+            Check::new("pip", "pip", Status::Pass, "24.0"),
+        ];
+        let report: serde_json::Value = serde_json::from_str(&json_report(&checks)).unwrap();
+        assert_eq!(
+            report,
+            json!({
+                "checks": [
+                    {"id": "python", "name": "Python", "status": "pass", "detail": "Python 3.12.1"},
+                    {"id": "node", "name": "Node.js", "status": "warn", "detail": "introuvable"},
+                    {"id": "django", "name": "Django", "status": "fail", "detail": "non installé"},
+                    {"id": "pip", "name": "pip", "status": "pass", "detail": "24.0"},
+                ],
+                "summary": {"pass": 2, "warn": 1, "fail": 1},
+            })
+        );
+    }
+}
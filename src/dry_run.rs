@@ -0,0 +1,311 @@
+use crate::error::{CliError, Result};
+use crate::utils::process::{describe_command, shell_quote};
+use minijinja::{context, Environment, Value};
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command as ShellCommand;
+use std::sync::Mutex;
+
+/// Files written by `django-admin startproject NAME`, relative to the project root.
+const STARTPROJECT_FILES: &[(&str, &str)] = &[
+    (
+        "manage.py",
+        include_str!("../templates/startproject/manage.py.j2"),
+    ),
+    ("{package}/__init__.py", ""),
+    (
+        "{package}/settings.py",
+        include_str!("../templates/startproject/settings.py.j2"),
+    ),
+    (
+        "{package}/urls.py",
+        include_str!("../templates/startproject/urls.py.j2"),
+    ),
+    (
+        "{package}/wsgi.py",
+        include_str!("../templates/startproject/wsgi.py.j2"),
+    ),
+    (
+        "{package}/asgi.py",
+        include_str!("../templates/startproject/asgi.py.j2"),
+    ),
+];
+
+/// Files written by `python manage.py startapp NAME`, relative to the app directory.
+const STARTAPP_FILES: &[(&str, &str)] = &[
+    ("__init__.py", ""),
+    (
+        "admin.py",
+        include_str!("../templates/startapp/admin.py.j2"),
+    ),
+    ("apps.py", include_str!("../templates/startapp/apps.py.j2")),
+    ("migrations/__init__.py", ""),
+    (
+        "models.py",
+        include_str!("../templates/startapp/models.py.j2"),
+    ),
+    (
+        "tests.py",
+        include_str!("../templates/startapp/tests.py.j2"),
+    ),
+    (
+        "views.py",
+        include_str!("../templates/startapp/views.py.j2"),
+    ),
+];
+
+/// Everything a dry run would have done, in memory.
+struct Plan {
+    /// Final content of every file written, by the CLI or by a simulated command.
+    files: BTreeMap<PathBuf, String>,
+    /// Content of the files before the CLI modified them: as found on disk, or as created by a
+    /// simulated command.
+    originals: BTreeMap<PathBuf, String>,
+    dirs: BTreeSet<PathBuf>,
+    commands: Vec<String>,
+    /// Django version whose `startproject` output is reproduced, e.g. `4.2`.
+    django_version: Option<String>,
+}
+
+static PLAN: Mutex<Option<Plan>> = Mutex::new(None);
+
+/// Switches the rest of the run to dry-run mode: files are written to an in-memory plan and
+/// commands are recorded instead of being run. See `print_report`.
+pub fn enable() {
+    *lock() = Some(Plan {
+        files: BTreeMap::new(),
+        originals: BTreeMap::new(),
+        dirs: BTreeSet::new(),
+        commands: Vec::new(),
+        django_version: None,
+    });
+}
+
+/// Whether the run is a dry run.
+pub fn is_enabled() -> bool {
+    lock().is_some()
+}
+
+/// Content of a file written during the dry run, if any.
+pub fn read(path: &Path) -> Option<String> {
+    lock().as_ref()?.files.get(&display_path(path)).cloned()
+}
+
+/// Whether a file or directory exists in the plan.
+pub fn exists(path: &Path) -> bool {
+    let path = display_path(path);
+    lock().as_ref().is_some_and(|plan| {
+        plan.dirs.contains(&path) || plan.files.keys().any(|file| file.starts_with(&path))
+    })
+}
+
+/// Records a file write. The first write of a file that exists on disk keeps its original
+/// content, so that the report can show the change as a diff.
+pub fn write(path: &Path, contents: &[u8]) {
+    if let Some(plan) = lock().as_mut() {
+        let key = display_path(path);
+        if !plan.files.contains_key(&key) {
+            if let Ok(original) = fs::read_to_string(path) {
+                plan.originals.insert(key.clone(), original);
+            }
+        }
+        plan.files
+            .insert(key, String::from_utf8_lossy(contents).into_owned());
+    }
+}
+
+/// Records the deletion of a file created earlier in the plan.
+pub fn remove(path: &Path) {
+    if let Some(plan) = lock().as_mut() {
+        let key = display_path(path);
+        plan.files.remove(&key);
+        plan.originals.remove(&key);
+    }
+}
+
+/// Records the creation of a directory.
+pub fn create_dir(path: &Path) {
+    if let Some(plan) = lock().as_mut() {
+        plan.dirs.insert(display_path(path));
+    }
+}
+
+/// Sets the Django version used to reproduce the output of `startproject`.
+pub fn set_django_version(version: &str) {
+    if let Some(plan) = lock().as_mut() {
+        plan.django_version = Some(version.to_string());
+    }
+}
+
+/// Records a command instead of running it, and simulates the files created by the Django
+/// scaffolding commands so that the following generators find what they expect.
+pub fn run(command: &ShellCommand) -> Result<()> {
+    let dir = command.get_current_dir().unwrap_or(Path::new("."));
+    let line = match dir.to_str() {
+        Some(".") | Some("") => describe_command(command),
+        _ => format!(
+            "cd {} && {}",
+            shell_quote(&display_path(dir).to_string_lossy()),
+            describe_command(command)
+        ),
+    };
+    if let Some(plan) = lock().as_mut() {
+        plan.commands.push(line);
+    }
+
+    let args: Vec<String> = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    // `django-admin ...` ou `python -m django ...`
+    let args = match args.as_slice() {
+        ["-m", "django", rest @ ..] => rest,
+        other => other,
+    };
+
+    match args {
+        ["startproject", name, rest @ ..] => {
+            let root = dir.join(rest.first().unwrap_or(name));
+            let version = lock()
+                .as_ref()
+                .and_then(|plan| plan.django_version.clone())
+                .unwrap_or_default();
+            let context = context! {
+                project_name => name,
+                secret_key => "django-insecure-<clé générée par django-admin>",
+                django_version => version,
+                docs_version => version,
+            };
+            for (file, source) in STARTPROJECT_FILES {
+                let path = root.join(file.replace("{package}", name));
+                simulate(&path, source, context.clone())?;
+            }
+        }
+        ["manage.py", "startapp", app] => {
+            let context = context! {
+                app_name => app,
+                camel_case_app_name => camel_case(app),
+            };
+            for (file, source) in STARTAPP_FILES {
+                simulate(&dir.join(app).join(file), source, context.clone())?;
+            }
+        }
+        ["init", "react-app", target] | ["-m", "venv", target] => create_dir(&dir.join(target)),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Prints the plan: the tree of files that would be created, the diffs of the files that
+/// would be modified, and the commands in the order they would run.
+pub fn print_report() {
+    let guard = lock();
+    let Some(plan) = guard.as_ref() else {
+        return;
+    };
+
+    println!();
+    println!("========================================");
+    println!("  Simulation (--dry-run) : rien n'a été modifié");
+    println!("========================================");
+
+    println!();
+    println!("Fichiers qui seraient créés :");
+    let mut entries: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut dirs: BTreeSet<PathBuf> = plan.dirs.iter().map(|dir| display_path(dir)).collect();
+    for path in plan.dirs.iter().chain(plan.files.keys()) {
+        let path = display_path(path);
+        dirs.extend(path.ancestors().skip(1).map(Path::to_path_buf));
+        entries.extend(path.ancestors().map(Path::to_path_buf));
+    }
+    for entry in entries.iter().filter(|entry| !entry.as_os_str().is_empty()) {
+        let depth = entry.components().count() - 1;
+        let name = entry
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let suffix = if dirs.contains(entry) { "/" } else { "" };
+        println!("{}{}{}", "    ".repeat(depth), name, suffix);
+    }
+
+    let modified: Vec<(&PathBuf, &String, &String)> = plan
+        .files
+        .iter()
+        .filter_map(|(path, contents)| {
+            let original = plan.originals.get(path)?;
+            (original != contents).then_some((path, original, contents))
+        })
+        .collect();
+    if !modified.is_empty() {
+        println!();
+        println!("Modifications des fichiers générés :");
+        for (path, original, contents) in modified {
+            let name = display_path(path).display().to_string();
+            print!(
+                "{}",
+                TextDiff::from_lines(original, contents)
+                    .unified_diff()
+                    .header(&format!("a/{}", name), &format!("b/{}", name))
+            );
+        }
+    }
+
+    println!();
+    println!("Commandes qui seraient exécutées :");
+    for (i, command) in plan.commands.iter().enumerate() {
+        println!("{:>3}. {}", i + 1, command);
+    }
+}
+
+fn simulate(path: &Path, source: &str, context: Value) -> Result<()> {
+    let mut env = Environment::new();
+    env.set_keep_trailing_newline(true);
+    let contents = env
+        .render_str(source, context)
+        .map_err(|source| CliError::Template {
+            name: path.display().to_string(),
+            source,
+        })?;
+
+    if let Some(plan) = lock().as_mut() {
+        let key = display_path(path);
+        plan.originals.insert(key.clone(), contents.clone());
+        plan.files.insert(key, contents);
+    }
+    Ok(())
+}
+
+/// `my_app` -> `MyApp`, as done by `startapp` for the `AppConfig` class name.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Path relative to the current directory when it is below it, without its leading `./`.
+/// Used both as the key of the plan and in the report.
+fn display_path(path: &Path) -> PathBuf {
+    let path = env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Plan>> {
+    PLAN.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
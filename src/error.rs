@@ -0,0 +1,168 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors reported by the CLI. Each variant maps to a distinct process exit code.
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// The project configuration is incomplete or invalid.
+    #[error("configuration invalide :\n{}", format_list(.0))]
+    InvalidConfig(Vec<String>),
+
+    /// A required external tool is not installed or not on the PATH.
+    #[error("outil introuvable : `{tool}` ({hint})")]
+    ToolMissing { tool: String, hint: String },
+
+    /// An external command exited with a failure status.
+    #[error("la commande `{cmd}` a échoué ({})\n{}", format_status(*.status), indent(.stderr))]
+    CommandFailed {
+        cmd: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+
+    /// A file system or terminal operation failed.
+    #[error("{context} : {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A template could not be loaded or rendered.
+    #[error("template `{name}` invalide : {source}")]
+    Template {
+        name: String,
+        #[source]
+        source: minijinja::Error,
+    },
+
+    /// No Django project was found where one was expected.
+    #[error("aucun projet Django détecté dans {} (manage.py introuvable)", .0.display())]
+    ProjectNotFound(PathBuf),
+
+    /// The operation is not supported on this platform.
+    #[error("système non pris en charge : {0}")]
+    UnsupportedPlatform(String),
+
+    /// `doctor`, or the verification of a generated project, found problems that prevent the
+    /// project from working.
+    #[error("{0} vérification(s) en échec")]
+    ChecksFailed(usize),
+
+    /// A settings module could not be edited.
+    #[error("impossible de modifier {} : {message}", .path.display())]
+    Settings { path: PathBuf, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, CliError>;
+
+impl CliError {
+    /// Wraps an I/O error with a short description of what was being done.
+    pub fn io(context: impl Into<String>, source: io::Error) -> CliError {
+        CliError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Process exit code for this error, stable so that scripts can react to it.
+    ///
+    /// | Code | Error |
+    /// | ---- | ----- |
+    /// | 2 | invalid configuration |
+    /// | 3 | missing tool |
+    /// | 4 | failed command |
+    /// | 5 | I/O error |
+    /// | 6 | template error |
+    /// | 7 | project not found |
+    /// | 8 | unsupported platform |
+    /// | 9 | failed `doctor` checks or project verification |
+    /// | 10 | settings module that cannot be edited |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::InvalidConfig(_) => 2,
+            CliError::ToolMissing { .. } => 3,
+            CliError::CommandFailed { .. } => 4,
+            CliError::Io { .. } => 5,
+            CliError::Template { .. } => 6,
+            CliError::ProjectNotFound(_) => 7,
+            CliError::UnsupportedPlatform(_) => 8,
+            CliError::ChecksFailed(_) => 9,
+            CliError::Settings { .. } => 10,
+        }
+    }
+}
+
+fn format_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("  - {}", item))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_status(status: Option<i32>) -> String {
+    match status {
+        Some(code) => format!("code de sortie {}", code),
+        None => "interrompue par un signal".to_string(),
+    }
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct_and_stable() {
+        let errors = [
+            CliError::InvalidConfig(vec!["champ `db_type` manquant".to_string()]),
+            CliError::ToolMissing {
+                tool: "npm".to_string(),
+                hint: "installez Node.js".to_string(),
+            },
+            CliError::CommandFailed {
+                cmd: "pip install -r requirements.txt".to_string(),
+                status: Some(1),
+                stderr: String::new(),
+            },
+            CliError::io(
+                "lecture de settings.py",
+                io::Error::from(io::ErrorKind::NotFound),
+            ),
+            CliError::Template {
+                name: "settings/cache.py".to_string(),
+                source: minijinja::Error::new(minijinja::ErrorKind::TemplateNotFound, "absent"),
+            },
+            CliError::ProjectNotFound(PathBuf::from("shop")),
+            CliError::UnsupportedPlatform("plan9".to_string()),
+            CliError::ChecksFailed(2),
+            CliError::Settings {
+                path: PathBuf::from("shop/settings.py"),
+                message: "INSTALLED_APPS introuvable".to_string(),
+            },
+        ];
+        let codes: Vec<i32> = errors.iter().map(CliError::exit_code).collect();
+        assert_eq!(codes, [2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn failed_commands_show_their_output() {
+        let error = CliError::CommandFailed {
+            cmd: "npm install".to_string(),
+            status: None,
+            stderr: "ERR! network\nERR! retry".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "la commande `npm install` a échoué (interrompue par un signal)\n    ERR! network\n    ERR! retry"
+        );
+    }
+}
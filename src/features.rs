@@ -0,0 +1,120 @@
+use crate::config::{CacheBackend, Feature, FeatureOptions};
+use crate::django::{
+    add_dependencies, applied_migrations, apply_feature, make_migrations, read_requirements,
+};
+use crate::error::{CliError, Result};
+use crate::project::ProjectLayout;
+use crate::settings::SettingsFile;
+use crate::utils::tools::select_choice;
+use std::path::Path;
+
+/// Adds a feature to the Django project found in `path`, then installs its dependencies.
+///
+/// Running it twice for the same feature leaves the project unchanged. The cache backend is
+/// asked interactively when `cache` is added without `--backend`; `email_login` only applies
+/// to `auth`, whose initial migration is generated once its dependencies are installed. `auth`
+/// is refused when the migrations of `django.contrib.auth` are already applied, as Django cannot
+/// switch the user model of a migrated database, unless `force` is set.
+pub fn add_feature(
+    path: &Path,
+    feature: Feature,
+    cache_backend: Option<CacheBackend>,
+    email_login: bool,
+    force: bool,
+) -> Result<()> {
+    let layout =
+        ProjectLayout::detect(path).ok_or_else(|| CliError::ProjectNotFound(path.to_path_buf()))?;
+
+    println!("Ajout de « {} » au projet {}...", feature, layout.package);
+
+    let cache_backend = match cache_backend {
+        Some(cache_backend) => cache_backend,
+        None if feature == Feature::Cache => {
+            select_choice("Quel système de cache souhaitez-vous configurer ?")?
+        }
+        None => CacheBackend::None,
+    };
+
+    let requirements = read_requirements(&layout)?;
+    let had_user_model = SettingsFile::open(&layout.settings_path())?.contains("AUTH_USER_MODEL");
+    if feature == Feature::Auth && !had_user_model {
+        match applied_migrations(&layout, "auth") {
+            Ok(applied) => check_user_model_swap(&applied, force)?,
+            // Base inaccessible ou Django absent : l'avertissement final reste affiché
+            Err(err) => eprintln!(
+                "Impossible de vérifier les migrations appliquées de auth : {}",
+                err
+            ),
+        }
+    }
+    apply_feature(
+        &layout,
+        feature,
+        &FeatureOptions {
+            cache_backend,
+            email_login,
+        },
+    )?;
+
+    // Seules les nouvelles dépendances sont transmises au gestionnaire de paquets
+    let added: Vec<String> = read_requirements(&layout)?
+        .into_iter()
+        .filter(|line| !requirements.contains(line))
+        .collect();
+    add_dependencies(&layout, &added)?;
+
+    if feature == Feature::Auth {
+        make_migrations(&layout, "authentication")?;
+        // Django ne sait pas changer de modèle utilisateur sur une base déjà migrée
+        if !had_user_model {
+            println!(
+                "Attention : AUTH_USER_MODEL pointe désormais vers authentication.CustomUser. \
+                 Si la base de données a déjà été migrée, recréez-la avant `manage.py migrate`."
+            );
+        }
+    }
+
+    println!(
+        "« {} » ajouté avec succès au projet {}.",
+        feature, layout.package
+    );
+    Ok(())
+}
+
+/// Refuses to point AUTH_USER_MODEL to a new model once migrations of `auth` are applied, unless
+/// `force` is set.
+fn check_user_model_swap(applied: &[String], force: bool) -> Result<()> {
+    if applied.is_empty() || force {
+        return Ok(());
+    }
+    Err(CliError::InvalidConfig(vec![format!(
+        "la base de données a déjà appliqué {} migration(s) de auth ({}) : Django ne peut pas \
+         changer de modèle utilisateur après coup. Recréez la base, ou passez --force pour \
+         ajouter authentication.CustomUser quand même",
+        applied.len(),
+        applied.join(", ")
+    )]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_is_refused_on_a_migrated_database() {
+        let applied = [
+            "0001_initial".to_string(),
+            "0002_alter_permission_name_max_length".to_string(),
+        ];
+        match check_user_model_swap(&applied, false) {
+            Err(CliError::InvalidConfig(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert!(errors[0].contains("0001_initial, 0002_alter_permission_name_max_length"));
+                assert!(errors[0].contains("--force"));
+            }
+            other => panic!("refus attendu, obtenu {:?}", other.err()),
+        }
+        assert!(check_user_model_swap(&applied, true).is_ok());
+        assert!(check_user_model_swap(&[], false).is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::project::ProjectLayout;
+use crate::utils::install::DjangoRelease;
+use crate::utils::interpreters::discover_interpreters;
+use crate::utils::tools::print_metadata;
+use std::path::Path;
+
+/// Prints the CLI metadata, the Python interpreters found on the machine, and a summary of the
+/// Django project found in `path`, if any.
+pub fn print_info(path: &Path) {
+    print_metadata();
+
+    println!("Interpréteurs Python :");
+    for python in discover_interpreters() {
+        match DjangoRelease::newest_for(python.version) {
+            Some(django) => println!("  {} -> Django {}", python, django),
+            None => println!("  {} -> non pris en charge par Django", python),
+        }
+    }
+
+    let Some(layout) = ProjectLayout::detect(path) else {
+        println!("Aucun projet Django détecté dans {}.", path.display());
+        return;
+    };
+
+    println!("Projet Django : {}", layout.package);
+    println!("Répertoire : {}", layout.root.display());
+    println!("Settings : {}", layout.settings_path().display());
+    match layout.python.venv_path() {
+        Some(venv) => println!("Environnement virtuel : {}", venv.display()),
+        None => println!("Environnement virtuel : aucun"),
+    }
+
+    let apps = layout.apps();
+    if apps.is_empty() {
+        println!("Applications : aucune");
+    } else {
+        println!("Applications : {}", apps.join(", "));
+    }
+
+    for file in ["requirements.txt", "frontend", "templates"] {
+        let status = if layout.root.join(file).exists() {
+            "présent"
+        } else {
+            "absent"
+        };
+        println!("{} : {}", file, status);
+    }
+}
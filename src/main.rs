@@ -1,27 +1,313 @@
 mod commands;
 mod config;
+mod dependencies;
 mod django;
+mod doctor;
+mod dry_run;
+mod error;
+mod features;
+mod info;
+mod project;
+mod settings;
+mod staging;
+mod templates;
 mod utils;
+mod verify;
+mod wheelhouse;
 
-use config::get_project_config;
-use django::{configure_django_project, create_django_project, install_dependencies};
+use clap::ArgMatches;
+use commands::setup_command;
+use config::{get_project_config, CacheBackend, Feature, PackageManager, ProjectConfig};
+use django::{
+    configure_django_project, create_django_project, install_dependencies, lock_dependencies,
+    make_migrations,
+};
+use doctor::{run_doctor, OutputFormat};
+use error::{CliError, Result};
+use features::add_feature;
+use info::print_info;
+use project::ProjectLayout;
+use staging::Staging;
+use std::path::{Path, PathBuf};
+use std::process;
+use utils::files::create_dir;
+use utils::install::{install_python, select_django_release, DjangoRelease};
+use utils::interpreters::Interpreter;
 use utils::tools::print_metadata;
+use utils::venv::PythonEnv;
+use verify::verify_project;
+use wheelhouse::{build_wheelhouse, check_wheelhouse};
 
 fn main() {
+    let matches = setup_command().get_matches();
+
+    // Les templates personnalisés s'appliquent à toutes les sous-commandes
+    templates::init(matches.get_one::<PathBuf>("templates_dir").cloned());
+
+    if let Err(err) = run(&matches) {
+        eprintln!("Erreur : {}", err);
+        process::exit(err.exit_code());
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<()> {
+    // Chaque sous-commande est un point d'entrée distinct
+    match matches.subcommand() {
+        Some(("new", sub_matches)) => run_new(sub_matches),
+        Some(("add", sub_matches)) => add_feature(
+            project_path(sub_matches),
+            *sub_matches
+                .get_one::<Feature>("feature")
+                .expect("feature is required"),
+            sub_matches
+                .get_one::<CacheBackend>("cache_backend")
+                .copied(),
+            sub_matches.get_flag("email_login"),
+            sub_matches.get_flag("force"),
+        ),
+        Some(("doctor", sub_matches)) => run_doctor(
+            project_path(sub_matches),
+            *sub_matches
+                .get_one::<OutputFormat>("format")
+                .expect("format has a default value"),
+        ),
+        Some(("info", sub_matches)) => {
+            print_info(project_path(sub_matches));
+            Ok(())
+        }
+        Some(("templates", sub_matches)) => run_templates(sub_matches),
+        Some(("wheelhouse", sub_matches)) => run_wheelhouse(sub_matches),
+        // Sans sous-commande, on conserve le comportement historique : créer un projet, avec les
+        // options de `new` passées directement
+        _ => run_new(matches),
+    }
+}
+
+fn run_new(matches: &ArgMatches) -> Result<()> {
     // Afficher les métadonnées en haut du terminal
     print_metadata();
 
     // Obtenir la configuration du projet à partir des arguments ou des invites utilisateur
-    let project_config = get_project_config();
+    let project_config = get_project_config(matches)?;
+    let project_dir = PathBuf::from(&project_config.project_name);
+
+    // L'environnement virtuel se trouve par défaut dans le projet (.venv)
+    let venv_path = if matches.get_flag("no_venv") {
+        None
+    } else {
+        Some(
+            matches
+                .get_one::<PathBuf>("venv_path")
+                .cloned()
+                .unwrap_or_else(|| project_dir.join(".venv")),
+        )
+    };
+
+    // En simulation, tout est enregistré dans un plan affiché à la fin, sans toucher au disque
+    let dry_run = matches.get_flag("dry_run");
+    if dry_run {
+        dry_run::enable();
+    }
+
+    // Vérifier le gestionnaire de paquets avant de créer quoi que ce soit
+    let package_manager = project_config.package_manager;
+    if let Some(program) = package_manager
+        .program()
+        .filter(|_| !package_manager.is_available())
+    {
+        return Err(CliError::ToolMissing {
+            tool: program.to_string(),
+            hint: "installez-le ou choisissez un autre --package-manager".to_string(),
+        });
+    }
+
+    // uv, Poetry, PDM et Pipenv installent dans leur propre environnement, que les commandes
+    // lancées ensuite avec le Python du système (manage.py, vérification) ne verraient pas
+    if venv_path.is_none() && package_manager != PackageManager::Pip {
+        return Err(CliError::InvalidConfig(vec![format!(
+            "--no-venv : {} installe les dépendances dans son propre environnement, utilisez pip \
+             ou retirez --no-venv",
+            package_manager
+        )]));
+    }
+
+    // Hors ligne, tout s'installe depuis le dossier de wheels, avec pip ou uv
+    let wheelhouse = matches.get_one::<PathBuf>("wheelhouse");
+    if let Some(wheelhouse) = wheelhouse {
+        check_wheelhouse(wheelhouse, package_manager)?;
+    }
+
+    // Choisir l'interpréteur et la version de Django ; sans --config, l'utilisateur choisit
+    // parmi les interpréteurs compatibles et confirme une éventuelle installation de Python
+    let python = install_python(
+        project_config.django_version,
+        project_config.python.as_deref(),
+        matches.get_one::<String>("config").is_none(),
+        matches.get_flag("allow_system_install"),
+    )?;
+    let django = select_django_release(python.version, project_config.django_version)?;
+    println!(
+        "{}, Django {}{}",
+        python,
+        django,
+        if django.lts { " LTS" } else { "" }
+    );
+
+    let options = GenerationOptions {
+        venv_path: venv_path.as_deref(),
+        wheelhouse: wheelhouse.map(PathBuf::as_path),
+        lock: matches.get_flag("lock"),
+        verify: !matches.get_flag("no_verify"),
+    };
+
+    if dry_run {
+        create_dir(&project_dir)?;
+        generate_project(&project_dir, &project_config, &python, django, &options)?;
+        dry_run::print_report();
+        return Ok(());
+    }
+
+    // Générer le projet à l'écart, puis le mettre en place seulement si toutes les étapes ont réussi
+    let staging = Staging::new(&project_dir, venv_path.as_deref())?;
+    let staged_venv = venv_path.as_deref().map(|path| staging.staged(path));
+    if let Err(err) = generate_project(
+        &staging.project_dir(),
+        &project_config,
+        &python,
+        django,
+        &GenerationOptions {
+            venv_path: staged_venv.as_deref(),
+            ..options
+        },
+    ) {
+        staging.rollback(matches.get_flag("keep_on_failure"));
+        return Err(err);
+    }
+    let project_dir = staging.commit()?;
+
+    println!(
+        "Projet Django configuré avec succès dans {} !",
+        project_dir.display()
+    );
+    if let Some(activate) = venv_path.and_then(|path| PythonEnv::venv(&path).activate_command()) {
+        println!("Activez l'environnement virtuel avec : {}", activate);
+    }
+    Ok(())
+}
+
+/// How `generate_project` installs and checks the project.
+struct GenerationOptions<'a> {
+    /// New virtual environment running Python, or `None` for the system interpreter.
+    venv_path: Option<&'a Path>,
+    /// Directory of wheels to install packages from, without network.
+    wheelhouse: Option<&'a Path>,
+    /// Record the installed versions in `requirements.lock`.
+    lock: bool,
+    /// Compile and check the generated project once installed.
+    verify: bool,
+}
+
+/// Creates, configures and installs the project in the existing `project_dir` with the chosen
+/// interpreter and Django release, then verifies it.
+fn generate_project(
+    project_dir: &Path,
+    project_config: &ProjectConfig,
+    python: &Interpreter,
+    django: &DjangoRelease,
+    options: &GenerationOptions,
+) -> Result<()> {
+    // Créer l'environnement virtuel avec l'interpréteur choisi
+    let python_env = match options.venv_path {
+        Some(venv_path) => PythonEnv::create(&python.path, venv_path)?,
+        None => PythonEnv::system(&python.path),
+    }
+    .with_wheelhouse(options.wheelhouse);
+    let layout = ProjectLayout::new(project_dir, &project_config.project_name)
+        .with_python(python_env)
+        .with_package_manager(project_config.package_manager);
+
+    // Noter les fichiers écrits par chaque étape pour la vérification finale
+    if options.verify {
+        verify::track(project_dir);
+    }
 
     // Créer le projet Django
-    create_django_project(&project_config.project_name);
+    verify::step("django-admin startproject");
+    create_django_project(&layout, django)?;
 
     // Configurer le projet Django
-    configure_django_project(&project_config);
+    configure_django_project(&layout, project_config, django, python.version)?;
 
     // Installer les dépendances
-    install_dependencies(&project_config.project_name);
+    verify::step("installation des dépendances");
+    install_dependencies(&layout)?;
+
+    // Le modèle utilisateur doit avoir sa migration avant toute autre
+    if project_config.auth_enabled {
+        verify::step("migrations initiales");
+        make_migrations(&layout, "authentication")?;
+    }
+
+    // Figer les versions installées
+    if options.lock {
+        verify::step("requirements.lock");
+        lock_dependencies(&layout)?;
+    }
+
+    // Compiler et vérifier le projet avec le Python de l'environnement virtuel
+    if options.verify {
+        verify_project(&layout)?;
+    }
+    Ok(())
+}
+
+fn run_templates(matches: &ArgMatches) -> Result<()> {
+    if let Some(("export", export_matches)) = matches.subcommand() {
+        let output = export_matches
+            .get_one::<PathBuf>("output")
+            .expect("output is required");
+
+        let written = templates::export_templates(output, export_matches.get_flag("force"))?;
+        for path in written {
+            println!("{}", path.display());
+        }
+        println!(
+            "Templates exportés. Utilisez --templates-dir {} ou copiez-les dans {}.",
+            output.display(),
+            templates::user_templates_dir()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "~/.config/django_cli/templates".to_string())
+        );
+    }
+
+    Ok(())
+}
+
+fn run_wheelhouse(matches: &ArgMatches) -> Result<()> {
+    if let Some(("build", build_matches)) = matches.subcommand() {
+        let output = build_matches
+            .get_one::<PathBuf>("output")
+            .expect("output is required");
+
+        // Les wheels dépendent de la version de Python : on choisit l'interpréteur comme `new`
+        let project_config = get_project_config(build_matches)?;
+        let python = install_python(
+            project_config.django_version,
+            project_config.python.as_deref(),
+            build_matches.get_one::<String>("config").is_none(),
+            false,
+        )?;
+        let django = select_django_release(python.version, project_config.django_version)?;
+        println!("{}, Django {}", python, django);
+
+        build_wheelhouse(&project_config, &python, django, output)?;
+    }
+
+    Ok(())
+}
 
-    println!("Projet Django configuré avec succès !");
+fn project_path(matches: &ArgMatches) -> &PathBuf {
+    matches
+        .get_one::<PathBuf>("path")
+        .expect("path has a default value")
 }
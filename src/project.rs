@@ -0,0 +1,120 @@
+use crate::config::PackageManager;
+use crate::utils::files::path_exists;
+use crate::utils::venv::PythonEnv;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Layout of an existing Django project, as created by `django-admin startproject`.
+pub struct ProjectLayout {
+    pub root: PathBuf,
+    pub package: String,
+    /// Interpreter running `manage.py` and pip for this project.
+    pub python: PythonEnv,
+    /// Tool installing the project dependencies.
+    pub package_manager: PackageManager,
+}
+
+impl ProjectLayout {
+    /// Layout of a project whose settings package is `package`, rooted at `root`.
+    /// Its interpreter is `python` from the PATH and its dependencies are installed with pip
+    /// until `with_python` and `with_package_manager` are called.
+    pub fn new(root: impl Into<PathBuf>, package: &str) -> ProjectLayout {
+        ProjectLayout {
+            root: root.into(),
+            package: package.to_string(),
+            python: PythonEnv::system("python"),
+            package_manager: PackageManager::Pip,
+        }
+    }
+
+    /// Sets the interpreter used for the project commands.
+    pub fn with_python(mut self, python: PythonEnv) -> ProjectLayout {
+        self.python = python;
+        self
+    }
+
+    /// Sets the tool installing the project dependencies.
+    pub fn with_package_manager(mut self, package_manager: PackageManager) -> ProjectLayout {
+        self.package_manager = package_manager;
+        self
+    }
+
+    /// Detects a Django project in the given directory by reading the settings module from `manage.py`.
+    /// Its interpreter is found by `PythonEnv::detect`, its package manager by `PackageManager::detect`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory expected to contain `manage.py`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ProjectLayout)` if `manage.py` and the settings module it points to exist,
+    /// * `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(layout) = ProjectLayout::detect(Path::new(".")) {
+    ///     println!("Settings: {}", layout.settings_path().display());
+    /// }
+    /// ```
+    pub fn detect(dir: &Path) -> Option<ProjectLayout> {
+        let manage_py = fs::read_to_string(dir.join("manage.py")).ok()?;
+
+        let package = manage_py
+            .lines()
+            .find(|line| line.contains("DJANGO_SETTINGS_MODULE"))
+            .and_then(|line| line.split(['\'', '"']).nth(3))
+            .and_then(|module| module.split('.').next())
+            .map(|package| package.to_string())?;
+
+        let layout = ProjectLayout::new(dir, &package)
+            .with_python(PythonEnv::detect(dir))
+            .with_package_manager(PackageManager::detect(dir));
+
+        layout.settings_path().is_file().then_some(layout)
+    }
+
+    /// Path of the settings that features extend: `settings.py`, or `settings/base.py` once the
+    /// settings are split by environment.
+    pub fn settings_path(&self) -> PathBuf {
+        if self.has_split_settings() {
+            self.settings_dir().join("base.py")
+        } else {
+            self.root.join(&self.package).join("settings.py")
+        }
+    }
+
+    /// Directory of the settings package (`settings/base.py`, `settings/production.py`, ...).
+    pub fn settings_dir(&self) -> PathBuf {
+        self.root.join(&self.package).join("settings")
+    }
+
+    /// Whether the settings are split by environment in a `settings/` package.
+    pub fn has_split_settings(&self) -> bool {
+        path_exists(&self.settings_dir().join("base.py"))
+    }
+
+    /// Path of the project `requirements.txt`.
+    pub fn requirements_path(&self) -> PathBuf {
+        self.root.join("requirements.txt")
+    }
+
+    /// Path of the `requirements-dev.txt` listing the development tools of a pip project.
+    pub fn dev_requirements_path(&self) -> PathBuf {
+        self.root.join("requirements-dev.txt")
+    }
+
+    /// Names of the Django apps found at the project root (directories containing an `apps.py`).
+    pub fn apps(&self) -> Vec<String> {
+        let mut apps: Vec<String> = fs::read_dir(&self.root)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join("apps.py").is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        apps.sort();
+        apps
+    }
+}
@@ -0,0 +1,784 @@
+//! Structural editing of Django settings modules.
+//!
+//! Instead of appending lines that override earlier values, the editor finds the top-level
+//! assignments of a settings module, such as `INSTALLED_APPS = [...]`, and edits their list
+//! literals in place, keeping the comments and the layout of the rest of the file. It understands
+//! what `startproject` and this CLI write: top-level assignments and imports, list and dict
+//! literals, strings and comments.
+
+use crate::error::{CliError, Result};
+use crate::utils::files::{read_file, write_file};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Where a new entry goes in a list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    First,
+    Last,
+}
+
+/// A settings module loaded for editing. Changes are kept in memory until `save`.
+pub struct SettingsFile {
+    path: PathBuf,
+    source: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Name,
+    Str,
+    Open,
+    Close,
+    Comma,
+    Colon,
+    Equals,
+    PlusEquals,
+    Newline,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+struct Lexeme {
+    token: Token,
+    span: Range<usize>,
+}
+
+/// A top-level statement: the lexemes of one logical line starting at column 0.
+struct Statement {
+    lexemes: Range<usize>,
+    span: Range<usize>,
+}
+
+impl SettingsFile {
+    /// Loads a settings module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut settings = SettingsFile::open(&layout.settings_path())?;
+    /// settings.add_to_list("INSTALLED_APPS", "'api'", Position::Last)?;
+    /// settings.save()?;
+    /// ```
+    pub fn open(path: &Path) -> Result<SettingsFile> {
+        Ok(SettingsFile {
+            path: path.to_path_buf(),
+            source: read_file(path)?,
+        })
+    }
+
+    /// Writes the edited module back to its file.
+    pub fn save(&self) -> Result<()> {
+        write_file(&self.path, &self.source)
+    }
+
+    /// The current source of the module.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Name of the module file, for messages.
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Whether the module assigns `name` at the top level.
+    pub fn contains(&self, name: &str) -> bool {
+        let lexemes = lex(&self.source);
+        !self.assignments(&lexemes, name).is_empty()
+    }
+
+    /// Replaces the value of the top-level assignment of `name`, or appends the assignment at
+    /// the end of the module if there is none.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The setting, e.g. `"DEBUG"`.
+    /// * `value` - The new value, as Python source, e.g. `"env.bool('DEBUG', default=False)"`.
+    pub fn set(&mut self, name: &str, value: &str) {
+        let lexemes = lex(&self.source);
+        let last = self
+            .assignments(&lexemes, name)
+            .into_iter()
+            .rfind(|(_, token)| *token == Token::Equals)
+            .map(|(statement, _)| statement);
+
+        match last {
+            Some(statement) => {
+                let value_lexemes = &lexemes[statement.lexemes.start + 2..statement.lexemes.end];
+                let start = value_lexemes
+                    .first()
+                    .map_or(statement.span.end, |l| l.span.start);
+                let end = value_lexemes.last().map_or(start, |l| l.span.end);
+                self.source.replace_range(start..end, value);
+            }
+            None => self.append(&format!("{} = {}\n", name, value)),
+        }
+    }
+
+    /// Adds an import statement after the existing top-level imports, unless the module already
+    /// has it.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the import was added, `false` if it was already there.
+    pub fn add_import(&mut self, import: &str) -> bool {
+        let lexemes = lex(&self.source);
+        let statements = statements(&self.source, &lexemes);
+        let normalized = normalize(import);
+
+        if statements
+            .iter()
+            .any(|statement| normalize(&self.source[statement.span.clone()]) == normalized)
+        {
+            return false;
+        }
+
+        // Après le docstring et les imports qui ouvrent le module
+        let mut position = 0;
+        for (index, statement) in statements.iter().enumerate() {
+            let first = &lexemes[statement.lexemes.start];
+            let keyword = &self.source[first.span.clone()];
+            let is_docstring = index == 0 && first.token == Token::Str;
+            if is_docstring || matches!(keyword, "import" | "from") {
+                position = statement.span.end;
+            } else {
+                break;
+            }
+        }
+
+        let mut text = format!("{}\n", import.trim());
+        if position > 0 && !self.source[..position].ends_with('\n') {
+            text.insert(0, '\n');
+        }
+        self.source.insert_str(position, &text);
+        true
+    }
+
+    /// Inserts a block of code right after the top-level assignment of `name`, or at the end of
+    /// the module if there is none.
+    pub fn insert_after(&mut self, name: &str, block: &str) {
+        let lexemes = lex(&self.source);
+        let end = self
+            .assignments(&lexemes, name)
+            .first()
+            .map(|(statement, _)| statement.span.end);
+
+        match end {
+            Some(end) => {
+                let mut text = block.to_string();
+                if !self.source[..end].ends_with('\n') {
+                    text.insert(0, '\n');
+                }
+                self.source.insert_str(end, &text);
+            }
+            None => self.append(block),
+        }
+    }
+
+    /// Adds an entry to the list assigned to `name`, e.g. an app to `INSTALLED_APPS`, unless the
+    /// list, or a later `name += [...]`, already contains it. A missing list is created at the end
+    /// of the module.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The setting, e.g. `"MIDDLEWARE"`.
+    /// * `entry` - The entry, as Python source, e.g. `"'corsheaders'"`.
+    /// * `position` - Whether the entry goes first or last.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the entry was added, `Ok(false)` if it was already listed,
+    /// * `Err(CliError::Settings)` if `name` is not assigned a list literal.
+    pub fn add_to_list(&mut self, name: &str, entry: &str, position: Position) -> Result<bool> {
+        let lexemes = lex(&self.source);
+
+        let mut target = None;
+        for (statement, token) in self.assignments(&lexemes, name) {
+            let open = statement.lexemes.start + 2;
+            let is_list = open < statement.lexemes.end
+                && lexemes[open].token == Token::Open
+                && matches!(&self.source[lexemes[open].span.clone()], "[" | "(");
+            if !is_list {
+                if token == Token::Equals {
+                    target = None;
+                }
+                continue;
+            }
+            if self.list_contains(&lexemes, open, entry) {
+                return Ok(false);
+            }
+            if token == Token::Equals {
+                target = Some(open);
+            }
+        }
+
+        match target {
+            Some(open) => {
+                self.insert_into_list(&lexemes, open, entry, position);
+                Ok(true)
+            }
+            None if !self.contains(name) => {
+                self.append(&format!("{} = [\n    {},\n]\n", name, entry));
+                Ok(true)
+            }
+            None => Err(self.error(format!("{} n'est pas une liste littérale", name))),
+        }
+    }
+
+    /// Adds a directory to the `DIRS` of the first engine of `TEMPLATES`, unless it is already
+    /// listed.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` if the directory was added, `Ok(false)` if it was already listed,
+    /// * `Err(CliError::Settings)` if `TEMPLATES` has no `'DIRS'` list.
+    pub fn add_template_dir(&mut self, entry: &str) -> Result<bool> {
+        let lexemes = lex(&self.source);
+        let statement = self
+            .assignments(&lexemes, "TEMPLATES")
+            .into_iter()
+            .rfind(|(_, token)| *token == Token::Equals)
+            .map(|(statement, _)| statement)
+            .ok_or_else(|| self.error("TEMPLATES introuvable".to_string()))?;
+
+        // La première clé 'DIRS' suivie d'une liste
+        let open = (statement.lexemes.start..statement.lexemes.end.saturating_sub(2))
+            .find(|&index| {
+                lexemes[index].token == Token::Str
+                    && unquote(&self.source[lexemes[index].span.clone()]) == Some("DIRS")
+                    && lexemes[index + 1].token == Token::Colon
+                    && &self.source[lexemes[index + 2].span.clone()] == "["
+            })
+            .map(|index| index + 2)
+            .ok_or_else(|| self.error("TEMPLATES ne contient pas de liste 'DIRS'".to_string()))?;
+
+        if self.list_contains(&lexemes, open, entry) {
+            return Ok(false);
+        }
+        self.insert_into_list(&lexemes, open, entry, Position::Last);
+        Ok(true)
+    }
+
+    /// Top-level assignments to `name`, with their operator (`=` or `+=`).
+    fn assignments(&self, lexemes: &[Lexeme], name: &str) -> Vec<(Statement, Token)> {
+        statements(&self.source, lexemes)
+            .into_iter()
+            .filter_map(|statement| {
+                let first = lexemes.get(statement.lexemes.start)?;
+                let operator = lexemes.get(statement.lexemes.start + 1)?;
+                let is_assignment = first.token == Token::Name
+                    && &self.source[first.span.clone()] == name
+                    && matches!(operator.token, Token::Equals | Token::PlusEquals);
+                let token = operator.token;
+                is_assignment.then_some((statement, token))
+            })
+            .collect()
+    }
+
+    fn list_contains(&self, lexemes: &[Lexeme], open: usize, entry: &str) -> bool {
+        let entry = normalize(entry);
+        let (items, _) = list_items(lexemes, open);
+        items
+            .iter()
+            .any(|item| normalize(&self.source[item.clone()]) == entry)
+    }
+
+    /// Inserts an entry in the list opened at `lexemes[open]`, following its layout: one entry per
+    /// line with the indentation of the existing entries, or inline for a single-line list.
+    fn insert_into_list(
+        &mut self,
+        lexemes: &[Lexeme],
+        open: usize,
+        entry: &str,
+        position: Position,
+    ) {
+        let (items, close) = list_items(lexemes, open);
+        let open_pos = lexemes[open].span.start;
+        let close_pos = lexemes[close].span.start;
+        let close_indent = indentation(&self.source, close_pos).unwrap_or_default();
+        let multiline = self.source[open_pos..close_pos].contains('\n');
+
+        let Some(first) = items.first() else {
+            let text = if multiline {
+                format!("    {}{},\n", close_indent, entry)
+            } else {
+                entry.to_string()
+            };
+            let at = if multiline {
+                line_start(&self.source, close_pos)
+            } else {
+                close_pos
+            };
+            self.source.insert_str(at, &text);
+            return;
+        };
+
+        let indent = indentation(&self.source, first.start)
+            .unwrap_or_else(|| format!("    {}", close_indent));
+        let last = items.last().expect("items is not empty");
+        let trailing_comma = lexemes[close - 1].token == Token::Comma;
+
+        match (position, multiline) {
+            (Position::First, true) if !self.source[open_pos..first.start].contains('\n') => {
+                self.source.insert_str(first.start, &format!("{}, ", entry));
+            }
+            (Position::First, true) => {
+                let at = line_start(&self.source, first.start);
+                self.source
+                    .insert_str(at, &format!("{}{},\n", indent, entry));
+            }
+            (Position::First, false) => {
+                self.source.insert_str(first.start, &format!("{}, ", entry));
+            }
+            (Position::Last, true) => {
+                // Après la ligne du dernier élément, pour ne pas déplacer son commentaire
+                let after = if trailing_comma {
+                    lexemes[close - 1].span.end
+                } else {
+                    last.end
+                };
+                match self.source[after..close_pos].find('\n') {
+                    Some(offset) => {
+                        let at = after + offset + 1;
+                        self.source
+                            .insert_str(at, &format!("{}{},\n", indent, entry));
+                    }
+                    None => {
+                        self.source
+                            .insert_str(after, &format!("\n{}{},", indent, entry));
+                    }
+                }
+                if !trailing_comma {
+                    self.source.insert(last.end, ',');
+                }
+            }
+            (Position::Last, false) => {
+                // La virgule finale reste la dernière : `('a',)` devient `('a', 'b',)`
+                if trailing_comma {
+                    self.source
+                        .insert_str(lexemes[close - 1].span.end, &format!(" {},", entry));
+                } else {
+                    self.source.insert_str(last.end, &format!(", {}", entry));
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, text: &str) {
+        if !self.source.is_empty() && !self.source.ends_with('\n') {
+            self.source.push('\n');
+        }
+        self.source.push('\n');
+        self.source.push_str(text);
+    }
+
+    fn error(&self, message: String) -> CliError {
+        CliError::Settings {
+            path: self.path.clone(),
+            message,
+        }
+    }
+}
+
+/// Splits Python source into the lexemes the editor needs. Comments and whitespace are dropped,
+/// and `Newline` is only emitted at the end of a logical line, outside brackets.
+fn lex(source: &str) -> Vec<Lexeme> {
+    let bytes = source.as_bytes();
+    let mut lexemes = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            b'#' => {
+                i = source[i..]
+                    .find('\n')
+                    .map_or(bytes.len(), |offset| i + offset);
+                continue;
+            }
+            b'\\' if bytes.get(i + 1) == Some(&b'\n') => {
+                i += 2;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' | b'\x0c' => {
+                i += 1;
+                continue;
+            }
+            b'\n' => {
+                i += 1;
+                if depth > 0 {
+                    continue;
+                }
+                Token::Newline
+            }
+            b'\'' | b'"' => {
+                i = string_end(bytes, i);
+                Token::Str
+            }
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                i += 1;
+                Token::Open
+            }
+            b')' | b']' | b'}' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+                Token::Close
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b':' if bytes.get(i + 1) != Some(&b'=') => {
+                i += 1;
+                Token::Colon
+            }
+            b'+' if bytes.get(i + 1) == Some(&b'=') => {
+                i += 2;
+                Token::PlusEquals
+            }
+            // Comparaisons et affectations augmentées : `<=`, `>=`, `!=`, `==`, `-=`, `:=`...
+            b'<' | b'>' | b'!' | b'=' | b'-' | b'*' | b'/' | b'%' | b'&' | b'|' | b'^' | b'@'
+            | b':'
+                if bytes.get(i + 1) == Some(&b'=') =>
+            {
+                i += 2;
+                Token::Other
+            }
+            b'=' => {
+                i += 1;
+                Token::Equals
+            }
+            // Les identifiants Python peuvent contenir des caractères non ASCII
+            byte if is_name_start(byte) => {
+                while i < bytes.len() && (is_name_start(bytes[i]) || bytes[i].is_ascii_digit()) {
+                    i += 1;
+                }
+                // Préfixes de chaînes : r'...', f"...", b'...'
+                let is_prefix = i - start <= 2
+                    && source[start..i]
+                        .chars()
+                        .all(|c| matches!(c.to_ascii_lowercase(), 'r' | 'b' | 'f' | 'u'));
+                if is_prefix && matches!(bytes.get(i), Some(b'\'' | b'"')) {
+                    i = string_end(bytes, i);
+                    Token::Str
+                } else {
+                    Token::Name
+                }
+            }
+            _ => {
+                i += 1;
+                Token::Other
+            }
+        };
+        lexemes.push(Lexeme {
+            token,
+            span: start..i,
+        });
+    }
+
+    lexemes
+}
+
+/// Whether `byte` can start an identifier. Every byte of a multi-byte UTF-8 character is
+/// accepted, so identifiers always end on a character boundary.
+fn is_name_start(byte: u8) -> bool {
+    byte == b'_' || byte.is_ascii_alphabetic() || !byte.is_ascii()
+}
+
+/// Index right after the string literal whose opening quote is at `start`.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let triple = bytes.get(start..start + 3) == Some(&[quote, quote, quote][..]);
+    let mut i = start + if triple { 3 } else { 1 };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !triple => return i,
+            byte if byte == quote => {
+                if !triple {
+                    return i + 1;
+                }
+                if bytes.get(i..i + 3) == Some(&[quote, quote, quote][..]) {
+                    return i + 3;
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Top-level statements of the module, i.e. the logical lines starting at column 0.
+fn statements(source: &str, lexemes: &[Lexeme]) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+
+    for (index, lexeme) in lexemes.iter().enumerate() {
+        let is_end = lexeme.token == Token::Newline;
+        if !is_end && index + 1 < lexemes.len() {
+            continue;
+        }
+
+        let end = if is_end { index } else { index + 1 };
+        if end > start {
+            let first = &lexemes[start];
+            let line = line_start(source, first.span.start);
+            if line == first.span.start {
+                statements.push(Statement {
+                    lexemes: start..end,
+                    span: line..lexemes[index].span.end,
+                });
+            }
+        }
+        start = index + 1;
+    }
+
+    statements
+}
+
+/// Source ranges of the entries of the list opened at `lexemes[open]`, and the index of its
+/// closing bracket.
+fn list_items(lexemes: &[Lexeme], open: usize) -> (Vec<Range<usize>>, usize) {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item: Option<Range<usize>> = None;
+
+    for (index, lexeme) in lexemes.iter().enumerate().skip(open + 1) {
+        match lexeme.token {
+            Token::Close if depth == 0 => {
+                items.extend(item);
+                return (items, index);
+            }
+            Token::Comma if depth == 0 => {
+                items.extend(item.take());
+                continue;
+            }
+            Token::Open => depth += 1,
+            Token::Close => depth -= 1,
+            _ => {}
+        }
+        let span = lexeme.span.clone();
+        item = Some(match item {
+            Some(item) => item.start..span.end,
+            None => span,
+        });
+    }
+
+    (items, lexemes.len() - 1)
+}
+
+/// Start of the line containing `position`.
+fn line_start(source: &str, position: usize) -> usize {
+    source[..position].rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// Whitespace before `position` on its line, `None` if other code precedes it.
+fn indentation(source: &str, position: usize) -> Option<String> {
+    let prefix = &source[line_start(source, position)..position];
+    prefix
+        .chars()
+        .all(char::is_whitespace)
+        .then(|| prefix.to_string())
+}
+
+/// Compares Python snippets regardless of spacing and quote style.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == '"' { '\'' } else { c })
+        .collect()
+}
+
+/// Content of a simple string literal.
+fn unquote(literal: &str) -> Option<&str> {
+    literal
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| literal.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(source: &str) -> SettingsFile {
+        SettingsFile {
+            path: PathBuf::from("settings.py"),
+            source: source.to_string(),
+        }
+    }
+
+    const STARTPROJECT: &str = r#""""
+Django settings for demo project.
+"""
+
+from pathlib import Path
+
+BASE_DIR = Path(__file__).resolve().parent.parent
+
+INSTALLED_APPS = [
+    'django.contrib.admin',
+    'django.contrib.auth',
+]
+
+MIDDLEWARE = [
+    'django.middleware.security.SecurityMiddleware',
+    'django.middleware.common.CommonMiddleware',
+]
+"#;
+
+    #[test]
+    fn adds_last_entry_to_multiline_list() {
+        let mut file = settings(STARTPROJECT);
+        assert!(file
+            .add_to_list("INSTALLED_APPS", "'api'", Position::Last)
+            .unwrap());
+        assert!(file.source().contains(
+            "INSTALLED_APPS = [\n    'django.contrib.admin',\n    'django.contrib.auth',\n    'api',\n]\n"
+        ));
+    }
+
+    #[test]
+    fn adds_first_entry_to_middleware() {
+        let mut file = settings(STARTPROJECT);
+        assert!(file
+            .add_to_list(
+                "MIDDLEWARE",
+                "'corsheaders.middleware.CorsMiddleware'",
+                Position::First
+            )
+            .unwrap());
+        assert!(file.source().contains(
+            "MIDDLEWARE = [\n    'corsheaders.middleware.CorsMiddleware',\n    'django.middleware.security.SecurityMiddleware',\n"
+        ));
+    }
+
+    #[test]
+    fn adds_comma_after_last_entry_without_trailing_comma() {
+        let mut file = settings("INSTALLED_APPS = [\n    'a',\n    'b'\n]\n");
+        file.add_to_list("INSTALLED_APPS", "'c'", Position::Last)
+            .unwrap();
+        assert_eq!(
+            file.source(),
+            "INSTALLED_APPS = [\n    'a',\n    'b',\n    'c',\n]\n"
+        );
+    }
+
+    #[test]
+    fn adds_entry_to_inline_list() {
+        let mut file = settings("INSTALLED_APPS = ['a', 'b']\nMIDDLEWARE = ('m',)\n");
+        file.add_to_list("INSTALLED_APPS", "'c'", Position::Last)
+            .unwrap();
+        file.add_to_list("MIDDLEWARE", "'n'", Position::Last)
+            .unwrap();
+        file.add_to_list("MIDDLEWARE", "'first'", Position::First)
+            .unwrap();
+        assert_eq!(
+            file.source(),
+            "INSTALLED_APPS = ['a', 'b', 'c']\nMIDDLEWARE = ('first', 'm', 'n',)\n"
+        );
+    }
+
+    #[test]
+    fn keeps_entry_already_listed() {
+        let mut file = settings(STARTPROJECT);
+        assert!(!file
+            .add_to_list("INSTALLED_APPS", "\"django.contrib.auth\"", Position::Last)
+            .unwrap());
+        assert_eq!(file.source(), STARTPROJECT);
+
+        let mut file = settings("INSTALLED_APPS = ['a']\nINSTALLED_APPS += ['b']\n");
+        assert!(!file
+            .add_to_list("INSTALLED_APPS", "'b'", Position::Last)
+            .unwrap());
+    }
+
+    #[test]
+    fn creates_missing_list_and_rejects_other_values() {
+        let mut file = settings("DEBUG = True\n");
+        assert!(file
+            .add_to_list("INSTALLED_APPS", "'a'", Position::Last)
+            .unwrap());
+        assert_eq!(
+            file.source(),
+            "DEBUG = True\n\nINSTALLED_APPS = [\n    'a',\n]\n"
+        );
+
+        let mut file = settings("INSTALLED_APPS = get_apps()\n");
+        assert!(file
+            .add_to_list("INSTALLED_APPS", "'a'", Position::Last)
+            .is_err());
+    }
+
+    #[test]
+    fn adds_import_after_docstring_and_imports() {
+        let mut file = settings(STARTPROJECT);
+        assert!(file.add_import("import environ"));
+        assert!(file
+            .source()
+            .contains("from pathlib import Path\nimport environ\n\nBASE_DIR"));
+        assert!(!file.add_import("import  environ"));
+
+        let mut file = settings("\"\"\"Settings.\"\"\"\n\nDEBUG = True\n");
+        file.add_import("import os");
+        assert_eq!(
+            file.source(),
+            "\"\"\"Settings.\"\"\"\nimport os\n\nDEBUG = True\n"
+        );
+    }
+
+    #[test]
+    fn set_replaces_multiline_assignment() {
+        let mut file = settings("ALLOWED_HOSTS = [\n    'a',\n    'b',\n]\nDEBUG = True\n");
+        file.set("ALLOWED_HOSTS", "env.list('ALLOWED_HOSTS', default=[])");
+        assert_eq!(
+            file.source(),
+            "ALLOWED_HOSTS = env.list('ALLOWED_HOSTS', default=[])\nDEBUG = True\n"
+        );
+    }
+
+    #[test]
+    fn set_appends_missing_assignment() {
+        let mut file = settings("DEBUG = True");
+        file.set("AUTH_USER_MODEL", "'authentication.CustomUser'");
+        assert_eq!(
+            file.source(),
+            "DEBUG = True\n\nAUTH_USER_MODEL = 'authentication.CustomUser'\n"
+        );
+    }
+
+    #[test]
+    fn handles_non_ascii_identifiers() {
+        let mut file = settings("é = 1\nnom_façade = 'café'\nDEBUG = True\n");
+        assert!(file.contains("é"));
+        assert!(file.contains("nom_façade"));
+        file.set("DEBUG", "False");
+        assert!(file.add_import("import os"));
+        assert_eq!(
+            file.source(),
+            "import os\né = 1\nnom_façade = 'café'\nDEBUG = False\n"
+        );
+    }
+
+    #[test]
+    fn comparison_operators_are_not_assignments() {
+        for operator in ["<=", ">=", "==", "!=", "-=", ":="] {
+            let source = format!("DEBUG {} 1\n", operator);
+            let file = settings(&source);
+            assert!(!file.contains("DEBUG"), "{}", operator);
+        }
+
+        let mut file = settings("LIMIT = 1 if A >= 2 else 3\n");
+        file.set("LIMIT", "4");
+        assert_eq!(file.source(), "LIMIT = 4\n");
+    }
+}
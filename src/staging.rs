@@ -0,0 +1,330 @@
+use crate::error::{CliError, Result};
+use crate::utils::files::create_dir;
+use crate::utils::venv::relocate_venv;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Staging directory in which a new project is generated before being moved into place.
+///
+/// The staging directory is a hidden sibling of the target, so that `commit` is a rename on the
+/// same file system: the target either does not exist or contains a fully generated project.
+/// A virtual environment generated inside the project moves with it, and `commit` rewrites the
+/// absolute paths it records. A virtual environment created outside the project (`--venv-path`)
+/// is generated in place and only removed by `rollback`.
+pub struct Staging {
+    dir: PathBuf,
+    target: PathBuf,
+    /// Final location of the virtual environment generated inside the project, if any.
+    venv: Option<PathBuf>,
+    /// Virtual environment outside the project that did not exist before the generation.
+    outside_venv: Option<PathBuf>,
+}
+
+impl Staging {
+    /// Creates the staging directory for a project that will live in `target`.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The final project directory, which must not exist yet.
+    /// * `venv_path` - The final location of the virtual environment the generation will create,
+    ///   if any. When it lies inside `target`, it is created in the staging directory (see
+    ///   `staged`) and relocated by `commit`. Otherwise it is removed by `rollback`, unless it
+    ///   already exists.
+    ///
+    /// # Returns
+    ///
+    /// * The staging area, or `CliError::Io` if `target` already exists or the directory cannot be created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let staging = Staging::new(Path::new("shop"), Some(Path::new("shop/.venv")))?;
+    /// let python = PythonEnv::create("python3", &staging.staged(Path::new("shop/.venv")))?;
+    /// create_django_project(&ProjectLayout::new(staging.project_dir(), "shop").with_python(python))?;
+    /// staging.commit()?;
+    /// ```
+    pub fn new(target: &Path, venv_path: Option<&Path>) -> Result<Staging> {
+        if target.exists() {
+            return Err(CliError::io(
+                format!("création de {}", target.display()),
+                io::Error::new(io::ErrorKind::AlreadyExists, "le dossier existe déjà"),
+            ));
+        }
+
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let dir = target.with_file_name(format!(".{}.staging-{}", name, process::id()));
+        create_dir(&dir.join(&name))?;
+
+        let (venv, outside_venv) = match venv_path {
+            Some(venv) if absolute(venv).starts_with(absolute(target)) => {
+                (Some(venv.to_path_buf()), None)
+            }
+            // Un environnement existant n'appartient pas à la génération
+            Some(venv) if !venv.exists() => (None, Some(venv.to_path_buf())),
+            _ => (None, None),
+        };
+
+        Ok(Staging {
+            dir,
+            target: target.to_path_buf(),
+            venv,
+            outside_venv,
+        })
+    }
+
+    /// Directory in which the project is generated, inside the staging directory.
+    pub fn project_dir(&self) -> PathBuf {
+        self.dir.join(self.target.file_name().unwrap_or_default())
+    }
+
+    /// Where to generate `path` so that it ends up there after `commit`: paths inside the target
+    /// are moved to the staged project, other paths are kept.
+    pub fn staged(&self, path: &Path) -> PathBuf {
+        match absolute(path).strip_prefix(absolute(&self.target)) {
+            Ok(relative) => self.project_dir().join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    /// Moves the generated project to its target, removes the staging directory and points the
+    /// virtual environment of the project to its new location.
+    pub fn commit(self) -> Result<PathBuf> {
+        let staged_project = absolute(&self.project_dir());
+        fs::rename(self.project_dir(), &self.target).map_err(|err| {
+            CliError::io(
+                format!(
+                    "déplacement de {} vers {}",
+                    self.project_dir().display(),
+                    self.target.display()
+                ),
+                err,
+            )
+        })?;
+        fs::remove_dir_all(&self.dir)
+            .map_err(|err| CliError::io(format!("suppression de {}", self.dir.display()), err))?;
+
+        if let Some(venv) = &self.venv {
+            relocate_venv(venv, &staged_project, &absolute(&self.target))?;
+        }
+
+        Ok(self.target)
+    }
+
+    /// Discards everything generated so far, or keeps it for inspection when `keep` is set.
+    pub fn rollback(self, keep: bool) {
+        if keep {
+            eprintln!(
+                "Fichiers générés conservés dans {} (--keep-on-failure).",
+                self.project_dir().display()
+            );
+            if let Some(venv) = &self.outside_venv {
+                eprintln!("Environnement virtuel conservé dans {}.", venv.display());
+            }
+            return;
+        }
+
+        let mut removed = true;
+        for dir in std::iter::once(&self.dir).chain(&self.outside_venv) {
+            match fs::remove_dir_all(dir) {
+                Ok(()) => {}
+                // L'environnement n'a pas forcément été créé avant l'échec
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    removed = false;
+                    eprintln!("Impossible de supprimer {} : {}", dir.display(), err);
+                }
+            }
+        }
+        if removed {
+            eprintln!("Génération annulée, aucun fichier n'a été conservé.");
+        }
+    }
+}
+
+/// `path` made absolute without touching the disk, as the paths recorded by `python -m venv`.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    /// Empty directory in which a test creates its project.
+    fn parent_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("django_cli-staging-{}-{}", test, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A generation that writes part of the project, then fails like a dependency installation.
+    fn failing_generation(staging: &Staging) -> Result<()> {
+        fs::create_dir(staging.project_dir().join("shop")).unwrap();
+        fs::write(staging.project_dir().join("manage.py"), "").unwrap();
+        Err(CliError::CommandFailed {
+            cmd: "pip install -r requirements.txt".to_string(),
+            status: Some(1),
+            stderr: String::new(),
+        })
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn a_failing_step_leaves_no_directory_behind() {
+        let parent = parent_dir("rollback");
+        let staging = Staging::new(&parent.join("shop"), None).unwrap();
+        assert!(failing_generation(&staging).is_err());
+        staging.rollback(false);
+
+        let left = entries(&parent);
+        fs::remove_dir_all(&parent).unwrap();
+        assert!(left.is_empty(), "{:?}", left);
+    }
+
+    #[test]
+    fn keep_on_failure_keeps_the_staged_project() {
+        let parent = parent_dir("keep");
+        let staging = Staging::new(&parent.join("shop"), None).unwrap();
+        let project_dir = staging.project_dir();
+        assert!(failing_generation(&staging).is_err());
+        staging.rollback(true);
+
+        let kept = project_dir.join("manage.py").is_file();
+        let target_exists = parent.join("shop").exists();
+        fs::remove_dir_all(&parent).unwrap();
+        assert!(kept);
+        assert!(!target_exists);
+    }
+
+    #[test]
+    fn a_venv_outside_the_project_is_removed_only_if_generated() {
+        let parent = parent_dir("outside-venv");
+        let (new_venv, existing_venv) = (parent.join("envs/new"), parent.join("envs/existing"));
+        fs::create_dir_all(&existing_venv).unwrap();
+
+        for (name, venv, keep) in [
+            ("shop", &new_venv, true),
+            ("shop", &new_venv, false),
+            ("store", &existing_venv, false),
+        ] {
+            let staging = Staging::new(&parent.join(name), Some(venv)).unwrap();
+            assert_eq!(staging.staged(venv), *venv);
+            fs::create_dir_all(venv).unwrap();
+            assert!(failing_generation(&staging).is_err());
+            staging.rollback(keep);
+            if keep {
+                assert!(new_venv.is_dir());
+                fs::remove_dir_all(parent.join(format!(".shop.staging-{}", process::id())))
+                    .unwrap();
+                fs::remove_dir_all(&new_venv).unwrap();
+            }
+        }
+
+        let (new_left, existing_left) = (new_venv.exists(), existing_venv.exists());
+        let left = entries(&parent);
+        fs::remove_dir_all(&parent).unwrap();
+        assert!(!new_left);
+        assert!(existing_left);
+        assert_eq!(left, ["envs"]);
+    }
+
+    #[test]
+    fn commit_moves_the_project_into_place() {
+        let parent = parent_dir("commit");
+        let target = parent.join("shop");
+        let staging = Staging::new(&target, None).unwrap();
+        fs::write(staging.project_dir().join("manage.py"), "").unwrap();
+        let committed = staging.commit().unwrap();
+
+        let left = entries(&parent);
+        let moved = target.join("manage.py").is_file();
+        let refused = Staging::new(&target, None).is_err();
+        fs::remove_dir_all(&parent).unwrap();
+        assert_eq!(committed, target);
+        assert_eq!(left, ["shop"]);
+        assert!(moved);
+        assert!(refused);
+    }
+
+    #[test]
+    fn the_project_venv_follows_the_project() {
+        const VENV_SCRIPTS: &str = if cfg!(windows) { "Scripts" } else { "bin" };
+
+        let parent = parent_dir("venv");
+        let target = parent.join("shop");
+        let staging = Staging::new(&target, Some(&target.join(".venv"))).unwrap();
+        let venv = staging.staged(&target.join(".venv"));
+        let staged_venv = absolute(&venv).to_string_lossy().into_owned();
+        assert!(venv.starts_with(staging.project_dir()));
+        assert_eq!(
+            staging.staged(&parent.join("envs").join("shop")),
+            parent.join("envs").join("shop")
+        );
+
+        let scripts = venv.join(VENV_SCRIPTS);
+        fs::create_dir_all(&scripts).unwrap();
+        fs::write(
+            venv.join("pyvenv.cfg"),
+            format!(
+                "home = /usr/bin\ncommand = /usr/bin/python3 -m venv {}\n",
+                staged_venv
+            ),
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("activate"),
+            format!("VIRTUAL_ENV=\"{}\"\nexport VIRTUAL_ENV\n", staged_venv),
+        )
+        .unwrap();
+        fs::write(
+            scripts.join("django-admin"),
+            format!("#!{}/bin/python\nimport sys\n", staged_venv),
+        )
+        .unwrap();
+        let launcher = [0x4d, 0x5a, 0xff, 0xfe];
+        fs::write(scripts.join("launcher.exe"), launcher).unwrap();
+        staging.commit().unwrap();
+
+        let final_venv = absolute(&target.join(".venv"))
+            .to_string_lossy()
+            .into_owned();
+        let read = |name: &str| fs::read(target.join(".venv").join(name)).unwrap();
+        let files = [
+            read("pyvenv.cfg"),
+            read(&format!("{}/activate", VENV_SCRIPTS)),
+            read(&format!("{}/django-admin", VENV_SCRIPTS)),
+            read(&format!("{}/launcher.exe", VENV_SCRIPTS)),
+        ];
+        fs::remove_dir_all(&parent).unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&files[0]),
+            format!(
+                "home = /usr/bin\ncommand = /usr/bin/python3 -m venv {}\n",
+                final_venv
+            )
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&files[1]),
+            format!("VIRTUAL_ENV=\"{}\"\nexport VIRTUAL_ENV\n", final_venv)
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&files[2]),
+            format!("#!{}/bin/python\nimport sys\n", final_venv)
+        );
+        assert_eq!(files[3], launcher);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::error::{CliError, Result};
+use crate::utils::files::{create_dir, write_file};
+use minijinja::{Environment, Error, ErrorKind};
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Templates of the generated files, compiled into the binary.
+/// Names mirror the paths under `templates/`, without the `.j2` extension.
+///
+/// `templates/startproject` and `templates/startapp` are not listed: they reproduce the output of
+/// `django-admin` for `--dry-run` only (see `dry_run.rs`), so they can be neither overridden nor
+/// exported.
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "settings/cache.py",
+        include_str!("../templates/settings/cache.py.j2"),
+    ),
+    (
+        "settings/cors.py",
+        include_str!("../templates/settings/cors.py.j2"),
+    ),
+    (
+        "settings/database.py",
+        include_str!("../templates/settings/database.py.j2"),
+    ),
+    (
+        "settings/email.py",
+        include_str!("../templates/settings/email.py.j2"),
+    ),
+    (
+        "settings/payment.py",
+        include_str!("../templates/settings/payment.py.j2"),
+    ),
+    (
+        "settings/environ.py",
+        include_str!("../templates/settings/environ.py.j2"),
+    ),
+    (
+        "settings/development.py",
+        include_str!("../templates/settings/development.py.j2"),
+    ),
+    (
+        "settings/production.py",
+        include_str!("../templates/settings/production.py.j2"),
+    ),
+    (
+        "settings/test.py",
+        include_str!("../templates/settings/test.py.j2"),
+    ),
+    (
+        "authentication/models.py",
+        include_str!("../templates/authentication/models.py.j2"),
+    ),
+    (
+        "authentication/forms.py",
+        include_str!("../templates/authentication/forms.py.j2"),
+    ),
+    (
+        "authentication/admin.py",
+        include_str!("../templates/authentication/admin.py.j2"),
+    ),
+    (
+        "project/base.html",
+        include_str!("../templates/project/base.html.j2"),
+    ),
+    (
+        "project/Dockerfile",
+        include_str!("../templates/project/Dockerfile.j2"),
+    ),
+    (
+        "project/docker-compose.yml",
+        include_str!("../templates/project/docker-compose.yml.j2"),
+    ),
+    (
+        "project/pyproject.toml",
+        include_str!("../templates/project/pyproject.toml.j2"),
+    ),
+    (
+        "project/Pipfile",
+        include_str!("../templates/project/Pipfile.j2"),
+    ),
+    (
+        "project/gitignore",
+        include_str!("../templates/project/gitignore.j2"),
+    ),
+    (
+        "project/dockerignore",
+        include_str!("../templates/project/dockerignore.j2"),
+    ),
+];
+
+static ENVIRONMENT: OnceLock<Environment<'static>> = OnceLock::new();
+
+/// Sets up template lookup for the rest of the run. Must be called before the first `render`.
+///
+/// Templates are looked up file by file in `templates_dir` (from `--templates-dir`), then in the
+/// user directory (`~/.config/django_cli/templates`), and finally among the built-in templates.
+///
+/// # Arguments
+///
+/// * `templates_dir` - An optional directory whose templates shadow all the others.
+pub fn init(templates_dir: Option<PathBuf>) {
+    let override_dirs: Vec<PathBuf> = templates_dir
+        .into_iter()
+        .chain(user_templates_dir())
+        .collect();
+    // Un second appel garde la première configuration
+    let _ = ENVIRONMENT.set(environment(override_dirs));
+}
+
+/// Builds the template environment, loading each template from the first override directory
+/// that contains it, or from the built-in templates.
+///
+/// Block tags swallow the newline that follows them, so templates can use one tag per line
+/// without leaving blank lines in the generated files.
+fn environment(override_dirs: Vec<PathBuf>) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+    env.set_keep_trailing_newline(true);
+
+    env.set_loader(move |name| {
+        for dir in &override_dirs {
+            let path = dir.join(format!("{}.j2", name));
+            match fs::read_to_string(&path) {
+                Ok(source) => return Ok(Some(source)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidOperation,
+                        format!("cannot read {}", path.display()),
+                    )
+                    .with_source(err))
+                }
+            }
+        }
+
+        Ok(TEMPLATES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, source)| source.to_string()))
+    });
+
+    env
+}
+
+/// The user templates directory: `$XDG_CONFIG_HOME/django_cli/templates`, `~/.config/django_cli/templates`
+/// or `%APPDATA%\django_cli\templates` on Windows.
+pub fn user_templates_dir() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(config_dir.join("django_cli").join("templates"))
+}
+
+/// Writes every built-in template to `dir`, keeping the layout expected by `--templates-dir`.
+///
+/// # Arguments
+///
+/// * `dir` - The destination directory, created if needed.
+/// * `force` - Whether existing files may be overwritten.
+///
+/// # Returns
+///
+/// * The paths of the written templates,
+/// * `Err(CliError::Io)` before anything is written if a template already exists without `force`,
+///   or the first write error.
+pub fn export_templates(dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let paths: Vec<PathBuf> = TEMPLATES
+        .iter()
+        .map(|(name, _)| dir.join(format!("{}.j2", name)))
+        .collect();
+
+    // Rien n'est écrit si un seul fichier existe déjà
+    if let Some(path) = paths.iter().find(|path| path.exists() && !force) {
+        return Err(CliError::io(
+            format!("export de {}", path.display()),
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "le fichier existe déjà (utilisez --force pour l'écraser)",
+            ),
+        ));
+    }
+
+    let mut written = Vec::new();
+    for (path, (_, source)) in paths.into_iter().zip(TEMPLATES) {
+        if let Some(parent) = path.parent() {
+            create_dir(parent)?;
+        }
+        write_file(&path, source)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Renders a template with the given context.
+///
+/// # Arguments
+///
+/// * `name` - The template name, e.g. `"settings/database.py"`.
+/// * `context` - Any serializable value, usually built with `minijinja::context!`.
+///
+/// # Returns
+///
+/// * A `String` containing the rendered file, or `CliError::Template` if the template is missing or invalid.
+///
+/// # Example
+///
+/// ```
+/// let email = render("settings/email.py", context! {})?;
+/// ```
+pub fn render<S: Serialize>(name: &str, context: S) -> Result<String> {
+    ENVIRONMENT
+        .get_or_init(|| environment(user_templates_dir().into_iter().collect()))
+        .get_template(name)
+        .and_then(|template| template.render(context))
+        .map_err(|source| CliError::Template {
+            name: name.to_string(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::context;
+
+    #[test]
+    fn builtin_templates_mirror_the_templates_directory() {
+        let templates_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+        for (name, source) in TEMPLATES {
+            let path = templates_dir.join(format!("{}.j2", name));
+            assert_eq!(
+                fs::read_to_string(&path).ok().as_deref(),
+                Some(*source),
+                "{}",
+                path.display()
+            );
+        }
+
+        // Seules les sorties de django-admin, simulées par --dry-run, ne sont pas enregistrées
+        for dir in fs::read_dir(&templates_dir).unwrap() {
+            let dir = dir.unwrap().path();
+            let group = dir.file_name().unwrap().to_string_lossy().into_owned();
+            for file in fs::read_dir(&dir).unwrap() {
+                let file = file.unwrap().file_name().to_string_lossy().into_owned();
+                let name = format!("{}/{}", group, file.trim_end_matches(".j2"));
+                let registered = TEMPLATES.iter().any(|(builtin, _)| *builtin == name);
+                let simulated = matches!(group.as_str(), "startproject" | "startapp");
+                assert!(registered != simulated, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn block_tags_leave_no_blank_lines() {
+        let cache = environment(Vec::new())
+            .get_template("settings/cache.py")
+            .and_then(|template| {
+                template.render(context! {
+                    cache_backend => "redis",
+                    backend => "django.core.cache.backends.redis.RedisCache",
+                    package => "shop",
+                })
+            })
+            .unwrap();
+        assert_eq!(
+            cache,
+            "CACHES = {
+    'default': {
+        'BACKEND': 'django.core.cache.backends.redis.RedisCache',
+        'LOCATION': env('REDIS_URL', default='redis://127.0.0.1:6379/1'),
+    }
+}
+"
+        );
+    }
+
+    #[test]
+    fn templates_are_looked_up_file_by_file() {
+        let dir = env::temp_dir().join(format!("django_cli-templates-{}", std::process::id()));
+        let (project_dir, user_dir) = (dir.join("project"), dir.join("user"));
+        for (templates_dir, name, source) in [
+            (&project_dir, "settings/email.py", "# email du projet\n"),
+            (&user_dir, "settings/email.py", "# email de l'utilisateur\n"),
+            (
+                &user_dir,
+                "settings/payment.py",
+                "# paiement de l'utilisateur\n",
+            ),
+        ] {
+            let path = templates_dir.join(format!("{}.j2", name));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        let env = environment(vec![project_dir, user_dir]);
+        let render = |name| env.get_template(name).unwrap().render(()).unwrap();
+        let rendered = [
+            render("settings/email.py"),
+            render("settings/payment.py"),
+            render("settings/environ.py"),
+        ];
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rendered[0], "# email du projet\n");
+        assert_eq!(rendered[1], "# paiement de l'utilisateur\n");
+        assert_eq!(
+            rendered[2],
+            include_str!("../templates/settings/environ.py.j2")
+        );
+    }
+
+    #[test]
+    fn export_writes_nothing_when_a_template_exists() {
+        let dir = env::temp_dir().join(format!("django_cli-export-{}", std::process::id()));
+        let existing = dir.join("settings").join("test.py.j2");
+        fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        fs::write(&existing, "custom").unwrap();
+
+        let result = export_templates(&dir, false);
+        let exported = TEMPLATES
+            .iter()
+            .filter(|(name, _)| dir.join(format!("{}.j2", name)).exists())
+            .count();
+        let forced = export_templates(&dir, true).map(|written| written.len());
+        let overwritten = fs::read_to_string(&existing).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(exported, 1);
+        assert_eq!(forced.ok(), Some(TEMPLATES.len()));
+        assert_ne!(overwritten, "custom");
+    }
+}
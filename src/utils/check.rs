@@ -1,5 +1,14 @@
+use std::ffi::OsStr;
+use std::fmt;
 use std::process::{Command as ShellCommand, Stdio};
 
+/// npm executable, a batch script on Windows.
+#[cfg(windows)]
+pub const NPM: &str = "npm.cmd";
+
+#[cfg(not(windows))]
+pub const NPM: &str = "npm";
+
 /// Checks if a given command is available on the system by trying to run it with the `--version` argument.
 ///
 /// # Arguments
@@ -30,41 +39,197 @@ pub fn is_command_available(command: &str) -> bool {
         .is_ok()
 }
 
+/// Version of a Python interpreter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PythonVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl PythonVersion {
+    /// Parses the output of `python --version`, e.g. `Python 3.12.1` or `Python 3.13.0rc2`.
+    /// Pre-release suffixes are ignored and a missing patch number counts as 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The text printed by the interpreter.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(PythonVersion)` if a `major.minor[.patch]` version follows the word `Python`,
+    /// * `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let version = PythonVersion::parse("Python 3.12.1").unwrap();
+    /// assert_eq!((version.major, version.minor, version.patch), (3, 12, 1));
+    /// ```
+    pub fn parse(output: &str) -> Option<PythonVersion> {
+        let mut words = output.split_whitespace();
+        words.find(|word| word.eq_ignore_ascii_case("python"))?;
+        let mut parts = words.next()?.split('.');
+
+        let major = parts.next()?.parse().ok()?;
+        let minor = leading_number(parts.next()?)?;
+        let patch = parts.next().and_then(leading_number).unwrap_or(0);
+
+        Some(PythonVersion {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// `(major, minor)`, the part of the version that matters for compatibility.
+    pub fn short(&self) -> (u32, u32) {
+        (self.major, self.minor)
+    }
+}
+
+impl fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Number at the start of `text` (`0rc2` -> 0).
+fn leading_number(text: &str) -> Option<u32> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
 /// Retrieves the version of Python by running the given command with the `--version` argument.
+/// Both stdout and stderr are read, as Python 2 and some builds print the version on stderr.
 ///
 /// # Arguments
 ///
-/// * `command` - A string slice representing the Python command to check (e.g., "python" or "python3").
+/// * `command` - The Python command to check (e.g., "python", "python3" or a path).
 ///
 /// # Returns
 ///
-/// * `Some((major, minor))` where `major` and `minor` are the major and minor version numbers of Python,
+/// * `Some(PythonVersion)` with the major, minor and patch numbers of Python,
 /// * `None` if the version could not be determined.
 ///
 /// # Example
 ///
 /// ```
-/// if let Some((major, minor)) = get_python_version("python") {
-///     println!("Python version: {}.{}", major, minor);
+/// if let Some(version) = get_python_version("python") {
+///     println!("Python version: {}", version);
 /// } else {
 ///     println!("Failed to retrieve Python version.");
 /// }
 /// ```
-pub fn get_python_version(command: &str) -> Option<(u32, u32)> {
+pub fn get_python_version(command: impl AsRef<OsStr>) -> Option<PythonVersion> {
     let output = ShellCommand::new(command).arg("--version").output().ok()?;
 
-    let version_string = String::from_utf8_lossy(&output.stdout);
-    let version_parts: Vec<&str> = version_string
-        .split_whitespace()
-        .nth(1)?
-        .split('.')
-        .collect();
-
-    if version_parts.len() >= 2 {
-        let major = version_parts[0].parse::<u32>().ok()?;
-        let minor = version_parts[1].parse::<u32>().ok()?;
-        Some((major, minor))
-    } else {
-        None
+    PythonVersion::parse(&String::from_utf8_lossy(&output.stdout))
+        .or_else(|| PythonVersion::parse(&String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Runs a tool and returns what it printed on stdout and stderr, whatever its exit status.
+///
+/// # Returns
+///
+/// * `Some(output)` if the tool could be started,
+/// * `None` if it is not installed.
+///
+/// # Example
+///
+/// ```
+/// let output = get_tool_output(ShellCommand::new("docker").arg("--version"));
+/// ```
+pub fn get_tool_output(command: &mut ShellCommand) -> Option<String> {
+    let output = command.output().ok()?;
+    Some(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Extracts the first dotted version number of a tool output, e.g. `16.2` from
+/// `psql (PostgreSQL) 16.2` or `20.11.0` from `v20.11.0`.
+pub fn parse_tool_version(output: &str) -> Option<String> {
+    output
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+        .map(|word| word.trim_start_matches('v'))
+        .find(|word| {
+            word.starts_with(|c: char| c.is_ascii_digit())
+                && word.contains('.')
+                && word.chars().all(|c| c.is_ascii_digit() || c == '.')
+        })
+        .map(str::to_string)
+}
+
+/// `(major, minor)` of a dotted version (`8.0.36` -> `(8, 0)`), a missing minor counting as 0.
+pub fn short_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), leading_number)?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn python_versions() {
+        let cases = [
+            ("Python 3.12.1", Some((3, 12, 1))),
+            ("Python 3.12.1\n", Some((3, 12, 1))),
+            ("Python 3.13.0rc1", Some((3, 13, 0))),
+            ("Python 3.14.0a2+", Some((3, 14, 0))),
+            ("Python 3.11", Some((3, 11, 0))),
+            ("python 3.8.18 :: Anaconda, Inc.", Some((3, 8, 18))),
+            (
+                "Python 3.10.12 (main, Nov 20 2023, 15:14:05) [GCC 11.4.0]",
+                Some((3, 10, 12)),
+            ),
+            ("Python 2.7.18", Some((2, 7, 18))),
+            ("Python", None),
+            ("Python 3", None),
+            ("pyenv: python3.12: command not found", None),
+            ("", None),
+        ];
+        for (output, expected) in cases {
+            let version = PythonVersion::parse(output)
+                .map(|version| (version.major, version.minor, version.patch));
+            assert_eq!(version, expected, "{:?}", output);
+        }
+    }
+
+    #[test]
+    fn python_versions_are_ordered() {
+        let parse = |output| PythonVersion::parse(output).unwrap();
+        assert!(parse("Python 3.9.18") < parse("Python 3.10.0"));
+        assert!(parse("Python 3.12.1") < parse("Python 3.12.10"));
+        assert_eq!(parse("Python 3.12.1").short(), (3, 12));
+    }
+
+    #[test]
+    fn short_versions() {
+        assert_eq!(short_version("5.2.7"), Some((5, 2)));
+        assert_eq!(short_version("5.2a1"), Some((5, 2)));
+        assert_eq!(short_version("5"), Some((5, 0)));
+        assert_eq!(short_version("dev"), None);
+    }
+
+    #[test]
+    fn tool_versions() {
+        assert_eq!(
+            parse_tool_version("psql (PostgreSQL) 16.2").as_deref(),
+            Some("16.2")
+        );
+        assert_eq!(parse_tool_version("v20.11.0").as_deref(), Some("20.11.0"));
+        assert_eq!(
+            parse_tool_version("Docker version 27.3.1, build ce12230").as_deref(),
+            Some("27.3.1")
+        );
+        assert_eq!(parse_tool_version("no version here"), None);
     }
 }
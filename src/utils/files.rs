@@ -0,0 +1,50 @@
+use crate::dry_run;
+use crate::error::{CliError, Result};
+use std::fs;
+use std::path::Path;
+
+/// Reads a whole file, reporting its path on failure.
+///
+/// All the helpers of this module go through the dry-run plan when `--dry-run` is active.
+pub fn read_file(path: &Path) -> Result<String> {
+    if let Some(contents) = dry_run::read(path) {
+        return Ok(contents);
+    }
+    fs::read_to_string(path)
+        .map_err(|err| CliError::io(format!("lecture de {}", path.display()), err))
+}
+
+/// Writes a whole file, reporting its path on failure.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    if dry_run::is_enabled() {
+        dry_run::write(path, contents.as_ref());
+        return Ok(());
+    }
+    fs::write(path, contents)
+        .map_err(|err| CliError::io(format!("écriture de {}", path.display()), err))
+}
+
+/// Creates a directory and its parents, reporting its path on failure.
+pub fn create_dir(path: &Path) -> Result<()> {
+    if dry_run::is_enabled() {
+        dry_run::create_dir(path);
+        return Ok(());
+    }
+    fs::create_dir_all(path)
+        .map_err(|err| CliError::io(format!("création de {}", path.display()), err))
+}
+
+/// Whether a file or directory exists, on disk or in the dry-run plan.
+pub fn path_exists(path: &Path) -> bool {
+    dry_run::exists(path) || path.exists()
+}
+
+/// Deletes a file, reporting its path on failure.
+pub fn remove_file(path: &Path) -> Result<()> {
+    if dry_run::is_enabled() {
+        dry_run::remove(path);
+        return Ok(());
+    }
+    fs::remove_file(path)
+        .map_err(|err| CliError::io(format!("suppression de {}", path.display()), err))
+}
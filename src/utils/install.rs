@@ -1,179 +1,717 @@
-use crate::utils::check::{get_python_version, is_command_available};
+use crate::dry_run;
+use crate::error::{CliError, Result};
+use crate::utils::check::is_command_available;
+use crate::utils::check::PythonVersion;
+use crate::utils::interpreters::{discover_interpreters, Interpreter, InterpreterSource};
+use crate::utils::process::run_command;
+use crate::utils::tools::{confirm, select_from};
+use crate::utils::venv::PythonEnv;
+use serde::Serialize;
 use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command as ShellCommand;
 
-const PYTHON_INSTALL_URL_WINDOWS: &str =
-    "https://www.python.org/ftp/python/3.12.0/python-3.12.0-amd64.exe";
-const DJANGO_PYTHON_COMPATIBILITY: &[(u32, u32, &str)] = &[
-    (3, 6, "3.2"),
-    (3, 7, "3.2"),
-    (3, 8, "4.2.8"),
-    (3, 9, "4.2.8"),
-    (3, 10, "4.2.8"),
-    (3, 11, "4.2.8"),
-    (3, 12, "4.2.8"),
+pub const PYTHON_DOWNLOAD_URL: &str = "https://www.python.org/downloads/";
+
+/// A Django feature release, with the Python versions and database servers it supports.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct DjangoRelease {
+    /// `(major, minor)`, e.g. `(4, 2)`.
+    pub version: (u32, u32),
+    /// Long-term support release.
+    pub lts: bool,
+    pub min_python: (u32, u32),
+    pub max_python: (u32, u32),
+    /// Oldest PostgreSQL server supported.
+    pub min_postgresql: (u32, u32),
+    /// Oldest MySQL server supported.
+    pub min_mysql: (u32, u32),
+}
+
+/// Supported Django releases, oldest first, from the Django FAQ
+/// (https://docs.djangoproject.com/en/stable/faq/install/#what-python-version-can-i-use-with-django)
+/// and the database notes of each release.
+pub const DJANGO_RELEASES: &[DjangoRelease] = &[
+    DjangoRelease {
+        version: (3, 2),
+        lts: true,
+        min_python: (3, 6),
+        max_python: (3, 10),
+        min_postgresql: (9, 6),
+        min_mysql: (5, 7),
+    },
+    DjangoRelease {
+        version: (4, 0),
+        lts: false,
+        min_python: (3, 8),
+        max_python: (3, 10),
+        min_postgresql: (10, 0),
+        min_mysql: (5, 7),
+    },
+    DjangoRelease {
+        version: (4, 1),
+        lts: false,
+        min_python: (3, 8),
+        max_python: (3, 11),
+        min_postgresql: (11, 0),
+        min_mysql: (5, 7),
+    },
+    DjangoRelease {
+        version: (4, 2),
+        lts: true,
+        min_python: (3, 8),
+        max_python: (3, 12),
+        min_postgresql: (12, 0),
+        min_mysql: (8, 0),
+    },
+    DjangoRelease {
+        version: (5, 0),
+        lts: false,
+        min_python: (3, 10),
+        max_python: (3, 12),
+        min_postgresql: (12, 0),
+        min_mysql: (8, 0),
+    },
+    DjangoRelease {
+        version: (5, 1),
+        lts: false,
+        min_python: (3, 10),
+        max_python: (3, 13),
+        min_postgresql: (13, 0),
+        min_mysql: (8, 0),
+    },
+    DjangoRelease {
+        version: (5, 2),
+        lts: true,
+        min_python: (3, 10),
+        max_python: (3, 14),
+        min_postgresql: (14, 0),
+        min_mysql: (8, 0),
+    },
 ];
 
-/// Ensures that Python is installed and compatible with Django.
-/// If no compatible Python version is found, installs Python 3.12 and sets Django 4.2.8 as the target version.
+impl DjangoRelease {
+    /// Release matching a version given by the user: `4.2`, or a full version such as `4.2.8`.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The requested Django version.
+    ///
+    /// # Returns
+    ///
+    /// * The release of the same major and minor version, or `None` if it is not in `DJANGO_RELEASES`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let release = DjangoRelease::find("4.2.8").unwrap();
+    /// assert!(release.lts);
+    /// ```
+    pub fn find(version: &str) -> Option<&'static DjangoRelease> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next().unwrap_or("0").parse().ok()?;
+
+        DJANGO_RELEASES
+            .iter()
+            .find(|release| release.version == (major, minor))
+    }
+
+    /// Newest LTS release supporting the interpreter, or the newest release supporting it if no
+    /// LTS does.
+    pub fn newest_for(python: PythonVersion) -> Option<&'static DjangoRelease> {
+        let supported = || {
+            DJANGO_RELEASES
+                .iter()
+                .rev()
+                .filter(move |release| release.supports(python))
+        };
+        supported()
+            .find(|release| release.lts)
+            .or_else(|| supported().next())
+    }
+
+    /// Whether the release supports the interpreter.
+    pub fn supports(&self, python: PythonVersion) -> bool {
+        (self.min_python..=self.max_python).contains(&python.short())
+    }
+
+    /// Supported Python versions, e.g. `3.8 à 3.12`.
+    pub fn python_range(&self) -> String {
+        format!(
+            "{}.{} à {}.{}",
+            self.min_python.0, self.min_python.1, self.max_python.0, self.max_python.1
+        )
+    }
+
+    /// Requirement line selecting the latest patch of the release, e.g. `Django>=4.2,<4.3`.
+    pub fn requirement(&self) -> String {
+        let (major, minor) = self.version;
+        format!("Django>={}.{},<{}.{}", major, minor, major, minor + 1)
+    }
+}
+
+impl fmt::Display for DjangoRelease {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.version.0, self.version.1)
+    }
+}
+
+/// Chooses the Python interpreter of the project, among the interpreters found by
+/// `discover_interpreters` that a Django release supports. If none is found, Python is installed
+/// with the package manager of the system (`SystemInstaller`), but only with `allow_system_install`
+/// or once the user has confirmed; otherwise manual instructions are printed.
 ///
-/// # Returns
+/// # Arguments
 ///
-/// * A `String` containing the path or command for the installed Python interpreter.
+/// * `django` - The Django release requested with `--django-version`, or `None` to accept any
+///   interpreter supported by a release of `DJANGO_RELEASES`.
+/// * `explicit` - The interpreter given with `--python`, used without discovery.
+/// * `interactive` - Whether the user picks among several compatible interpreters and is asked
+///   before a system installation; otherwise the first one of the PATH is used, then the newest.
+/// * `allow_system_install` - Whether Python may be installed system-wide without asking
+///   (`--allow-system-install`).
 ///
-/// # Panics
+/// # Returns
 ///
-/// * If the Python installation fails or no compatible Python version is found.
+/// * The interpreter,
+/// * `Err(CliError::ToolMissing)` if the `--python` interpreter cannot be run, or if no compatible
+///   interpreter is found and none could be installed,
+/// * `Err(CliError::InvalidConfig)` if the requested release does not support Python 3.12 either,
+/// * `Err(CliError::UnsupportedPlatform)` if none is found on an operating system without a
+///   supported package manager, or the error of the failed installer.
 ///
 /// # Example
 ///
 /// ```
-/// let python_command = install_python();
-/// println!("Using Python command: {}", python_command);
+/// let python = install_python(None, None, true, false)?;
+/// println!("Using {}", python);
 /// ```
-pub fn install_python() -> String {
-    let python_commands = ["python", "python3"];
-    let mut compatible_command = None;
-    let mut _django_version = None;
-
-    for command in &python_commands {
-        if is_command_available(command) {
-            if let Some((major, minor)) = get_python_version(command) {
-                for &(py_major, py_minor, django_ver) in DJANGO_PYTHON_COMPATIBILITY {
-                    if major == py_major && minor == py_minor {
-                        compatible_command = Some(command.to_string());
-                        _django_version = Some(django_ver);
-                        break;
-                    }
-                }
+pub fn install_python(
+    django: Option<&DjangoRelease>,
+    explicit: Option<&Path>,
+    interactive: bool,
+    allow_system_install: bool,
+) -> Result<Interpreter> {
+    if let Some(path) = explicit {
+        return Interpreter::inspect(path, InterpreterSource::Explicit).ok_or_else(|| {
+            CliError::ToolMissing {
+                tool: path.display().to_string(),
+                hint: "vérifiez le chemin passé à --python".to_string(),
             }
+        });
+    }
+
+    let is_compatible = |version| match django {
+        Some(django) => django.supports(version),
+        None => DjangoRelease::newest_for(version).is_some(),
+    };
+    let compatible: Vec<Interpreter> = discover_interpreters()
+        .into_iter()
+        .filter(|python| is_compatible(python.version))
+        .collect();
+
+    match compatible.as_slice() {
+        [] => {}
+        [python] => return Ok(python.clone()),
+        [python, ..] if !interactive => return Ok(python.clone()),
+        _ => {
+            return select_from(
+                "Quel interpréteur Python souhaitez-vous utiliser ?",
+                &compatible,
+            )
         }
     }
 
-    if compatible_command.is_none() {
-        println!("No compatible Python version found. Installing Python 3.12...");
+    // Python 3.12 n'aide pas si la version de Django demandée ne le prend pas en charge
+    let fallback = PythonVersion {
+        major: 3,
+        minor: 12,
+        patch: 0,
+    };
+    if let Some(django) = django.filter(|django| !django.supports(fallback)) {
+        return Err(CliError::InvalidConfig(vec![format!(
+            "Django {} requiert Python {} : aucun interpréteur compatible trouvé",
+            django,
+            django.python_range()
+        )]));
+    }
 
-        match env::consts::OS {
-            "windows" => {
-                if let Err(err) = ShellCommand::new("cmd")
-                    .args(["/C", "start", PYTHON_INSTALL_URL_WINDOWS])
-                    .status()
-                {
-                    panic!("Failed to download Python for Windows: {}", err);
-                }
-                println!("Please follow the instructions to install Python.");
-            }
-            "macos" => {
-                if let Err(err) = ShellCommand::new("brew")
-                    .arg("install")
-                    .arg("python@3.12")
-                    .status()
-                {
-                    panic!("Failed to install Python via Homebrew: {}", err);
-                }
-            }
-            "linux" => {
-                if let Err(err) = ShellCommand::new("sudo")
-                    .arg("apt-get")
-                    .arg("install")
-                    .arg("-y")
-                    .arg("python3.12")
-                    .status()
-                {
-                    panic!("Failed to install Python via apt: {}", err);
-                }
-            }
-            _ => panic!("Unsupported operating system"),
+    // Installer Python au niveau du système uniquement avec l'accord de l'utilisateur
+    let installer = SystemInstaller::detect();
+    let Some(installer) =
+        consented_installer(installer, interactive, allow_system_install, confirm)?
+    else {
+        print_manual_instructions(installer, django);
+        if !matches!(env::consts::OS, "linux" | "macos" | "windows") {
+            return Err(CliError::UnsupportedPlatform(env::consts::OS.to_string()));
         }
+        return Err(CliError::ToolMissing {
+            tool: "python".to_string(),
+            hint: "aucun interpréteur compatible, voir les instructions ci-dessus".to_string(),
+        });
+    };
 
-        compatible_command = Some("python3.12".to_string());
-        _django_version = Some("4.2.8");
+    println!("Installation de Python avec {}...", installer);
+    run_command(&mut installer.command())?;
+
+    // En simulation, rien n'a été installé : on suppose que Python 3.12 l'aurait été
+    if dry_run::is_enabled() {
+        return Ok(Interpreter {
+            path: PathBuf::from("python3"),
+            version: fallback,
+            source: InterpreterSource::Path,
+        });
+    }
+
+    discover_interpreters()
+        .into_iter()
+        .find(|python| is_compatible(python.version))
+        .ok_or_else(|| CliError::ToolMissing {
+            tool: "python".to_string(),
+            hint: format!(
+                "{} n'a pas installé de version compatible, utilisez --python",
+                installer
+            ),
+        })
+}
+
+/// System package manager able to install Python, used only with the user's consent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemInstaller {
+    Apt,
+    Dnf,
+    Pacman,
+    Apk,
+    Zypper,
+    Brew,
+    Winget,
+}
+
+impl SystemInstaller {
+    /// First package manager of the operating system found on the PATH.
+    pub fn detect() -> Option<SystemInstaller> {
+        SystemInstaller::select(env::consts::OS, is_command_available)
     }
 
-    compatible_command.expect("Python installation failed or is still unavailable.")
+    /// First package manager of `os` (as in `std::env::consts::OS`) whose program is available.
+    fn select(os: &str, is_available: impl Fn(&str) -> bool) -> Option<SystemInstaller> {
+        let candidates: &[SystemInstaller] = match os {
+            "linux" => &[
+                SystemInstaller::Apt,
+                SystemInstaller::Dnf,
+                SystemInstaller::Pacman,
+                SystemInstaller::Apk,
+                SystemInstaller::Zypper,
+            ],
+            "macos" => &[SystemInstaller::Brew],
+            "windows" => &[SystemInstaller::Winget],
+            _ => &[],
+        };
+
+        candidates
+            .iter()
+            .copied()
+            .find(|installer| is_available(installer.program()))
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            SystemInstaller::Apt => "apt-get",
+            SystemInstaller::Dnf => "dnf",
+            SystemInstaller::Pacman => "pacman",
+            SystemInstaller::Apk => "apk",
+            SystemInstaller::Zypper => "zypper",
+            SystemInstaller::Brew => "brew",
+            SystemInstaller::Winget => "winget",
+        }
+    }
+
+    /// Arguments installing a Python 3 recent enough for Django, with `venv` and pip.
+    fn install_args(&self) -> &'static [&'static str] {
+        match self {
+            SystemInstaller::Apt => &["install", "-y", "python3", "python3-venv", "python3-pip"],
+            SystemInstaller::Dnf => &["install", "-y", "python3", "python3-pip"],
+            SystemInstaller::Pacman => &["-S", "--noconfirm", "python", "python-pip"],
+            SystemInstaller::Apk => &["add", "python3", "py3-pip"],
+            SystemInstaller::Zypper => &["--non-interactive", "install", "python312"],
+            SystemInstaller::Brew => &["install", "python@3.12"],
+            SystemInstaller::Winget => &["install", "--exact", "--id", "Python.Python.3.12"],
+        }
+    }
+
+    /// Whether the command must run as root: Linux package managers, unless already root.
+    fn needs_sudo(&self) -> bool {
+        !matches!(self, SystemInstaller::Brew | SystemInstaller::Winget) && !is_root()
+    }
+
+    /// The install command as shown to the user, e.g. `sudo dnf install -y python3 python3-pip`.
+    pub fn command_line(&self) -> String {
+        let sudo = if self.needs_sudo() { "sudo " } else { "" };
+        format!(
+            "{}{} {}",
+            sudo,
+            self.program(),
+            self.install_args().join(" ")
+        )
+    }
+
+    fn command(&self) -> ShellCommand {
+        let mut command = if self.needs_sudo() {
+            let mut sudo = ShellCommand::new("sudo");
+            sudo.arg(self.program());
+            sudo
+        } else {
+            ShellCommand::new(self.program())
+        };
+        command.args(self.install_args());
+        command
+    }
+}
+
+impl fmt::Display for SystemInstaller {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.program())
+    }
+}
+
+/// Whether the CLI runs as root on a Unix system.
+fn is_root() -> bool {
+    ShellCommand::new("id")
+        .arg("-u")
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "0")
 }
 
-/// Ensures that pip is installed. If not, installs it using the Python interpreter found or installed by `install_python`.
+/// The package manager that may install Python: always with `allow_system_install`, once the
+/// user has agreed through `ask` in interactive mode, never otherwise.
+fn consented_installer(
+    installer: Option<SystemInstaller>,
+    interactive: bool,
+    allow_system_install: bool,
+    ask: impl FnOnce(&str) -> Result<bool>,
+) -> Result<Option<SystemInstaller>> {
+    let consent = match installer {
+        Some(installer) if !allow_system_install && interactive => ask(&format!(
+            "Aucun interpréteur Python compatible trouvé. Installer Python avec `{}` ?",
+            installer.command_line()
+        ))?,
+        Some(_) => allow_system_install,
+        None => false,
+    };
+    Ok(installer.filter(|_| consent))
+}
+
+/// Explains how to install Python by hand, with the command of the detected package manager and
+/// the Python versions supported by the requested release, or by any release of `DJANGO_RELEASES`.
+fn print_manual_instructions(installer: Option<SystemInstaller>, django: Option<&DjangoRelease>) {
+    for line in manual_instructions(installer, django) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Lines of the manual installation instructions.
+fn manual_instructions(
+    installer: Option<SystemInstaller>,
+    django: Option<&DjangoRelease>,
+) -> Vec<String> {
+    let versions = match django {
+        Some(django) => format!("Python {} pour Django {}", django.python_range(), django),
+        None => {
+            let (major, minor) = DJANGO_RELEASES
+                .iter()
+                .map(|release| release.min_python)
+                .min()
+                .expect("DJANGO_RELEASES is not empty");
+            format!("Python {}.{} ou plus récent", major, minor)
+        }
+    };
+    let mut lines = vec![
+        "Aucun interpréteur Python compatible avec Django n'a été trouvé.".to_string(),
+        format!("Installez {}, par exemple :", versions),
+    ];
+    if let Some(installer) = installer {
+        lines.push(format!("  {}", installer.command_line()));
+    }
+    lines.push("  pyenv install 3.12 && pyenv global 3.12".to_string());
+    lines.push(format!(
+        "  ou le programme d'installation de {}",
+        PYTHON_DOWNLOAD_URL
+    ));
+    lines.push(
+        "Relancez ensuite la commande, avec --python <chemin> pour un interpréteur hors du PATH, \
+         ou passez --allow-system-install pour laisser django_cli l'installer."
+            .to_string(),
+    );
+    lines
+}
+
+/// Django release to install for an interpreter: the requested one, or the newest LTS release
+/// supporting the interpreter.
+///
+/// # Arguments
+///
+/// * `python` - The version of the project interpreter.
+/// * `requested` - The release requested with `--django-version`, if any.
 ///
 /// # Returns
 ///
-/// * A `String` containing the command to run pip.
+/// * The release, or `CliError::InvalidConfig` if it does not support the interpreter.
+pub fn select_django_release(
+    python: PythonVersion,
+    requested: Option<&'static DjangoRelease>,
+) -> Result<&'static DjangoRelease> {
+    match requested {
+        Some(release) if release.supports(python) => Ok(release),
+        Some(release) => Err(CliError::InvalidConfig(vec![format!(
+            "Django {} requiert Python {}, l'interpréteur trouvé est Python {}",
+            release,
+            release.python_range(),
+            python
+        )])),
+        None => DjangoRelease::newest_for(python).ok_or_else(|| {
+            CliError::InvalidConfig(vec![format!(
+                "aucune version de Django prise en charge ne fonctionne avec Python {}",
+                python
+            )])
+        }),
+    }
+}
+
+/// Ensures that pip is available to the given interpreter. If not, installs it with `ensurepip`.
 ///
-/// # Panics
+/// # Arguments
 ///
-/// * If pip installation fails or pip is still unavailable after installation.
+/// * `python` - The interpreter that will run `python -m pip`.
+///
+/// # Returns
+///
+/// * `Ok(())` once pip is available,
+/// * `Err(CliError::ToolMissing)` if pip is still unavailable after installation.
 ///
 /// # Example
 ///
 /// ```
-/// let pip_command = install_pip();
-/// println!("Using pip command: {}", pip_command);
+/// install_pip(&PythonEnv::system("python3"))?;
 /// ```
-pub fn install_pip() -> String {
-    let python_command = install_python(); // Ensure Python is installed and get the command
-
-    let pip_commands = ["pip", "pip3"];
-
-    for command in &pip_commands {
-        if is_command_available(command) {
-            return command.to_string();
-        }
+pub fn install_pip(python: &PythonEnv) -> Result<()> {
+    if python.has_module("pip") {
+        return Ok(());
     }
 
     println!("pip is not installed. Installing pip...");
 
-    if let Err(err) = ShellCommand::new(python_command)
-        .arg("-m")
-        .arg("ensurepip")
-        .status()
-    {
-        panic!("Failed to install pip via ensurepip: {}", err);
-    }
+    run_command(python.python().arg("-m").arg("ensurepip"))?;
 
-    for command in &pip_commands {
-        if is_command_available(command) {
-            return command.to_string();
-        }
+    // En simulation, ensurepip n'a pas été lancé : on suppose qu'il aurait installé pip
+    if dry_run::is_enabled() || python.has_module("pip") {
+        return Ok(());
     }
 
-    panic!("Pip installation failed or is still unavailable.");
+    Err(CliError::ToolMissing {
+        tool: "pip".to_string(),
+        hint: "installez-le avec `python -m ensurepip --upgrade`".to_string(),
+    })
 }
 
-/// Ensures that Django is installed using pip. If not installed, it installs Django 4.2.8,
-/// particularly if Python 3.12 was installed by `install_python`.
+/// Ensures that the given Django release is installed in the interpreter. If Django is missing,
+/// or another release is installed in a virtual environment, it installs the latest patch of the
+/// given release.
+///
+/// # Arguments
 ///
-/// # Panics
+/// * `python` - The interpreter, usually the virtual environment of the project.
+/// * `django` - The release to install, as chosen by `select_django_release`.
 ///
-/// * If Django installation fails or `django-admin` is still unavailable after installation.
+/// # Returns
+///
+/// * `Ok(())` once the release can be imported,
+/// * `Err(CliError::InvalidConfig)` if the system interpreter (`--no-venv`) has another release,
+/// * `Err(CliError::ToolMissing)` if the release is still unavailable after installation.
 ///
 /// # Example
 ///
 /// ```
-/// install_django();
+/// install_django(&layout.python, DjangoRelease::find("4.2").unwrap())?;
 /// println!("Django installed and ready to use.");
 /// ```
-pub fn install_django() {
-    let pip_command = install_pip(); // Ensure pip is installed and get the command
-
-    let django_version = "4.2.8"; // Force the use of Django 4.2.8 in case of Python 3.12
-
-    if is_command_available("django-admin") {
-        println!("Django-admin is already installed.");
-    } else {
-        println!("Django-admin is not installed. Installing Django...");
-
-        if let Err(err) = ShellCommand::new(pip_command)
-            .arg("install")
-            .arg(format!("django=={}", django_version))
-            .status()
-        {
-            panic!("Failed to run pip to install Django: {}", err);
-        }
+pub fn install_django(python: &PythonEnv, django: &DjangoRelease) -> Result<()> {
+    // Les environnements créés par `python -m venv` contiennent déjà pip
+    if python.venv_path().is_none() {
+        install_pip(python)?;
+    }
 
-        if !is_command_available("django-admin") {
-            panic!("Django installation failed.");
-        } else {
-            println!("Django {} was successfully installed.", django_version);
+    match python.django_version() {
+        Some(installed) if DjangoRelease::find(&installed) == Some(django) => {
+            println!("Django {} is already installed.", installed);
+            return Ok(());
+        }
+        // On ne remplace pas le Django de l'interpréteur système
+        Some(installed) if python.venv_path().is_none() => {
+            return Err(CliError::InvalidConfig(vec![format!(
+                "Django {} est installé dans l'interpréteur système alors que Django {} a été choisi : \
+                 retirez --no-venv ou choisissez la version installée avec --django-version",
+                installed, django
+            )]));
+        }
+        Some(installed) => {
+            println!(
+                "Django {} is installed. Installing Django {} instead...",
+                installed, django
+            );
         }
+        None => println!("Django is not installed. Installing Django {}...", django),
+    }
+
+    run_command(python.pip_install().arg(django.requirement()))?;
+
+    let installed = python.django_version();
+    if !dry_run::is_enabled() && installed.as_deref().and_then(DjangoRelease::find) != Some(django)
+    {
+        return Err(CliError::ToolMissing {
+            tool: format!("Django {}", django),
+            hint: format!(
+                "l'installation par pip a laissé {}",
+                installed.map_or("Django introuvable".to_string(), |version| format!(
+                    "Django {}",
+                    version
+                ))
+            ),
+        });
+    }
+    println!("Django {} was successfully installed.", django);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn installers_are_picked_per_operating_system() {
+        let available =
+            |programs: &'static [&'static str]| move |program: &str| programs.contains(&program);
+        assert_eq!(
+            SystemInstaller::select("linux", available(&["pacman", "apt-get", "dnf"])),
+            Some(SystemInstaller::Apt)
+        );
+        assert_eq!(
+            SystemInstaller::select("linux", available(&["zypper", "brew"])),
+            Some(SystemInstaller::Zypper)
+        );
+        assert_eq!(
+            SystemInstaller::select("macos", available(&["apt-get", "brew"])),
+            Some(SystemInstaller::Brew)
+        );
+        assert_eq!(
+            SystemInstaller::select("windows", available(&["winget"])),
+            Some(SystemInstaller::Winget)
+        );
+        assert_eq!(
+            SystemInstaller::select("macos", available(&["apt-get"])),
+            None
+        );
+        assert_eq!(
+            SystemInstaller::select("freebsd", available(&["brew"])),
+            None
+        );
+    }
+
+    #[test]
+    fn python_is_installed_only_with_consent() {
+        let asked = Cell::new(0);
+        let answer = |consent| {
+            let asked = &asked;
+            move |question: &str| {
+                assert!(
+                    question.contains("brew install python@3.12"),
+                    "{}",
+                    question
+                );
+                asked.set(asked.get() + 1);
+                Ok(consent)
+            }
+        };
+        let brew = Some(SystemInstaller::Brew);
+
+        // L'utilisateur refuse : rien n'est installé
+        assert_eq!(
+            consented_installer(brew, true, false, answer(false)).unwrap(),
+            None
+        );
+        assert_eq!(
+            consented_installer(brew, true, false, answer(true)).unwrap(),
+            brew
+        );
+        assert_eq!(asked.get(), 2);
+
+        // Sans invite, seul --allow-system-install vaut accord
+        assert_eq!(
+            consented_installer(brew, false, false, answer(true)).unwrap(),
+            None
+        );
+        assert_eq!(
+            consented_installer(brew, false, true, answer(false)).unwrap(),
+            brew
+        );
+        assert_eq!(
+            consented_installer(brew, true, true, answer(false)).unwrap(),
+            brew
+        );
+        assert_eq!(
+            consented_installer(None, true, true, answer(true)).unwrap(),
+            None
+        );
+        assert_eq!(asked.get(), 2);
+    }
+
+    #[test]
+    fn a_closed_stdin_is_reported() {
+        let closed = |_: &str| {
+            Err(CliError::io(
+                "lecture de l'entrée standard",
+                std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+            ))
+        };
+        assert!(consented_installer(Some(SystemInstaller::Apt), true, false, closed).is_err());
+    }
+
+    #[test]
+    fn manual_instructions_show_the_package_manager_command() {
+        let with_brew = manual_instructions(Some(SystemInstaller::Brew), None);
+        assert!(with_brew.contains(&"  brew install python@3.12".to_string()));
+        assert!(with_brew
+            .iter()
+            .any(|line| line.contains(PYTHON_DOWNLOAD_URL)));
+        assert!(with_brew
+            .last()
+            .is_some_and(|line| line.contains("--allow-system-install")));
+
+        let without = manual_instructions(None, None);
+        assert_eq!(without.len(), with_brew.len() - 1);
+        assert!(!without.iter().any(|line| line.contains("brew")));
+    }
+
+    #[test]
+    fn manual_instructions_name_the_supported_python_versions() {
+        // Sans version de Django demandée, la plus ancienne version de Python connue suffit
+        let any = manual_instructions(None, None);
+        assert_eq!(any[1], "Installez Python 3.6 ou plus récent, par exemple :");
+
+        let django = DJANGO_RELEASES
+            .iter()
+            .find(|release| release.version == (4, 2))
+            .unwrap();
+        let lts = manual_instructions(None, Some(django));
+        assert_eq!(
+            lts[1],
+            format!(
+                "Installez Python {} pour Django 4.2, par exemple :",
+                django.python_range()
+            )
+        );
     }
 }
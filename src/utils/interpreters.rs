@@ -0,0 +1,420 @@
+use crate::utils::check::{get_python_version, PythonVersion};
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command as ShellCommand, Stdio};
+
+/// Location of the interpreter inside a Python installation prefix (pyenv, asdf, conda).
+#[cfg(windows)]
+const PREFIX_PYTHON: &str = "python.exe";
+
+#[cfg(not(windows))]
+const PREFIX_PYTHON: &str = "bin/python";
+
+/// Extension of executables on the PATH.
+#[cfg(windows)]
+const EXE_SUFFIX: &str = ".exe";
+
+#[cfg(not(windows))]
+const EXE_SUFFIX: &str = "";
+
+/// Where an interpreter was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterpreterSource {
+    Path,
+    Pyenv,
+    Asdf,
+    Conda,
+    /// Given with `--python`.
+    Explicit,
+}
+
+/// A Python interpreter found on the machine.
+#[derive(Clone, Debug)]
+pub struct Interpreter {
+    pub path: PathBuf,
+    pub version: PythonVersion,
+    pub source: InterpreterSource,
+}
+
+impl Interpreter {
+    /// Runs `path --version` to build an interpreter.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The interpreter executable, or a command looked up on the PATH.
+    /// * `source` - Where it was found.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Interpreter)` if the executable runs and prints a Python version,
+    /// * `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(python) = Interpreter::inspect(Path::new("/usr/bin/python3.12"), InterpreterSource::Explicit) {
+    ///     println!("{}", python);
+    /// }
+    /// ```
+    pub fn inspect(path: &Path, source: InterpreterSource) -> Option<Interpreter> {
+        let version = get_python_version(path)?;
+        Some(Interpreter {
+            path: path.to_path_buf(),
+            version,
+            source,
+        })
+    }
+}
+
+impl fmt::Display for InterpreterSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            InterpreterSource::Path => "PATH",
+            InterpreterSource::Pyenv => "pyenv",
+            InterpreterSource::Asdf => "asdf",
+            InterpreterSource::Conda => "conda",
+            InterpreterSource::Explicit => "--python",
+        })
+    }
+}
+
+impl fmt::Display for Interpreter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Python {} ({}, {})",
+            self.version,
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+/// Finds the Python interpreters installed on the machine: `python`, `python3` and `python3.X`
+/// on the PATH, pyenv and asdf versions, and conda environments.
+///
+/// Version manager shims are replaced by the interpreter they currently run, and an interpreter
+/// reachable from several places is listed once.
+///
+/// # Returns
+///
+/// * The interpreters of the PATH in PATH order, then the others, newest version first.
+///
+/// # Example
+///
+/// ```
+/// for python in discover_interpreters() {
+///     println!("{}", python);
+/// }
+/// ```
+pub fn discover_interpreters() -> Vec<Interpreter> {
+    let mut candidates: Vec<(PathBuf, InterpreterSource)> = Vec::new();
+
+    for dir in env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default()
+    {
+        // Une shim pyenv ou asdf lance la version active : on garde l'interpréteur réel
+        if is_shims_dir(&dir) {
+            candidates.extend(
+                ["python", "python3"]
+                    .iter()
+                    .map(|name| dir.join(format!("{}{}", name, EXE_SUFFIX)))
+                    .filter_map(|shim| resolve_shim(&shim))
+                    .map(|path| (path, InterpreterSource::Path)),
+            );
+            continue;
+        }
+        candidates.extend(
+            list_dir(&dir)
+                .into_iter()
+                .filter(|path| is_python_executable(path))
+                .map(|path| (path, InterpreterSource::Path)),
+        );
+    }
+
+    let pyenv_root = env::var_os("PYENV_ROOT")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".pyenv")));
+    if let Some(root) = pyenv_root {
+        candidates.extend(prefixes(&root.join("versions"), InterpreterSource::Pyenv));
+    }
+
+    let asdf_root = env::var_os("ASDF_DATA_DIR")
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".asdf")));
+    if let Some(root) = asdf_root {
+        candidates.extend(prefixes(
+            &root.join("installs").join("python"),
+            InterpreterSource::Asdf,
+        ));
+    }
+
+    candidates.extend(
+        conda_prefixes()
+            .into_iter()
+            .map(|prefix| (prefix.join(PREFIX_PYTHON), InterpreterSource::Conda)),
+    );
+
+    let mut interpreters: Vec<Interpreter> = unique_candidates(candidates)
+        .into_iter()
+        .filter_map(|(path, source)| Interpreter::inspect(&path, source))
+        .collect();
+    sort_interpreters(&mut interpreters);
+    interpreters
+}
+
+/// Existing candidates, each interpreter once: the first path found for it is kept.
+fn unique_candidates(
+    candidates: Vec<(PathBuf, InterpreterSource)>,
+) -> Vec<(PathBuf, InterpreterSource)> {
+    let mut seen = Vec::new();
+    candidates
+        .into_iter()
+        .filter(|(path, _)| path.is_file())
+        .filter(|(path, _)| {
+            // Les liens python -> python3 -> python3.12 désignent le même interpréteur
+            let real_path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+            if seen.contains(&real_path) {
+                return false;
+            }
+            seen.push(real_path);
+            true
+        })
+        .collect()
+}
+
+/// Sorts interpreters for display and selection.
+fn sort_interpreters(interpreters: &mut [Interpreter]) {
+    // L'ordre du PATH est celui du shell ; les autres interpréteurs suivent, du plus récent au plus ancien
+    interpreters.sort_by(|a, b| {
+        match (
+            a.source == InterpreterSource::Path,
+            b.source == InterpreterSource::Path,
+        ) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => b.version.cmp(&a.version),
+        }
+    });
+}
+
+/// Whether a file name is `python`, `python3` or `python3.X` (not `python3-config`).
+fn is_python_executable(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some(name) = name.strip_suffix(EXE_SUFFIX) else {
+        return false;
+    };
+
+    match name.strip_prefix("python") {
+        Some("") | Some("3") => true,
+        Some(version) => version
+            .strip_prefix("3.")
+            .is_some_and(|minor| !minor.is_empty() && minor.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Interpreter a version manager shim runs, as reported by `sys.executable`.
+fn resolve_shim(shim: &Path) -> Option<PathBuf> {
+    let output = ShellCommand::new(shim)
+        .arg("-c")
+        .arg("import sys; print(sys.executable)")
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let executable = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!executable.is_empty()).then(|| PathBuf::from(executable))
+}
+
+/// Directories of version manager shims.
+fn is_shims_dir(dir: &Path) -> bool {
+    dir.ends_with("shims")
+        && dir
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .is_some_and(|name| name == ".pyenv" || name == ".asdf" || name == "pyenv-win")
+}
+
+/// Interpreters of the installation prefixes found in `dir` (one sub-directory per version).
+fn prefixes(dir: &Path, source: InterpreterSource) -> Vec<(PathBuf, InterpreterSource)> {
+    list_dir(dir)
+        .into_iter()
+        .map(|prefix| (prefix.join(PREFIX_PYTHON), source))
+        .collect()
+}
+
+/// Conda installations and environments: the active one, those listed in
+/// `~/.conda/environments.txt`, and the usual installation directories with their `envs`.
+fn conda_prefixes() -> Vec<PathBuf> {
+    let mut bases: Vec<PathBuf> = Vec::new();
+    if let Some(conda_exe) = env::var_os("CONDA_EXE").map(PathBuf::from) {
+        // <base>/bin/conda
+        bases.extend(
+            conda_exe
+                .parent()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf),
+        );
+    }
+    if let Some(home) = home_dir() {
+        for name in [
+            "miniconda3",
+            "miniconda",
+            "anaconda3",
+            "miniforge3",
+            "mambaforge",
+        ] {
+            bases.push(home.join(name));
+        }
+    }
+
+    let mut prefixes: Vec<PathBuf> = env::var_os("CONDA_PREFIX")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    if let Some(home) = home_dir() {
+        let environments = fs::read_to_string(home.join(".conda").join("environments.txt"));
+        prefixes.extend(
+            environments
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+    for base in bases {
+        prefixes.extend(list_dir(&base.join("envs")));
+        prefixes.push(base);
+    }
+
+    prefixes
+}
+
+/// Entries of a directory, sorted; empty if it cannot be read.
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interpreter(path: &str, version: &str, source: InterpreterSource) -> Interpreter {
+        Interpreter {
+            path: PathBuf::from(path),
+            version: PythonVersion::parse(&format!("Python {}", version)).unwrap(),
+            source,
+        }
+    }
+
+    #[test]
+    fn python_executables() {
+        for name in ["python", "python3", "python3.12", "python3.9"] {
+            let path = PathBuf::from(format!("/usr/bin/{}{}", name, EXE_SUFFIX));
+            assert!(is_python_executable(&path), "{}", name);
+        }
+        for name in [
+            "python3-config",
+            "python3.12-config",
+            "python3.",
+            "python2",
+            "pythonw",
+            "pip",
+        ] {
+            let path = PathBuf::from(format!("/usr/bin/{}{}", name, EXE_SUFFIX));
+            assert!(!is_python_executable(&path), "{}", name);
+        }
+    }
+
+    #[test]
+    fn shims_dirs() {
+        assert!(is_shims_dir(Path::new("/home/me/.pyenv/shims")));
+        assert!(is_shims_dir(Path::new("/home/me/.asdf/shims")));
+        assert!(!is_shims_dir(Path::new("/home/me/.local/shims")));
+        assert!(!is_shims_dir(Path::new("/home/me/.pyenv/bin")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_to_the_same_interpreter_are_listed_once() {
+        use std::os::unix::fs::symlink;
+
+        let dir = env::temp_dir().join(format!("django_cli-interpreters-{}", std::process::id()));
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("python3.12"), "").unwrap();
+        fs::write(bin.join("python3.11"), "").unwrap();
+        symlink("python3.12", bin.join("python3")).unwrap();
+        symlink("python3", bin.join("python")).unwrap();
+        // Une shim cassée ou un interpréteur désinstallé n'est pas un candidat
+        symlink("python3.10", bin.join("python3.10-broken")).unwrap();
+
+        let candidates = [
+            "python",
+            "python3",
+            "python3.12",
+            "python3.11",
+            "python3.10-broken",
+        ]
+        .iter()
+        .map(|name| (bin.join(name), InterpreterSource::Path))
+        .chain([(bin.join("python3.12"), InterpreterSource::Pyenv)])
+        .collect();
+        let unique: Vec<PathBuf> = unique_candidates(candidates)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(unique, [bin.join("python"), bin.join("python3.11")]);
+    }
+
+    #[test]
+    fn path_interpreters_come_first() {
+        let mut interpreters = vec![
+            interpreter("/pyenv/3.11/bin/python", "3.11.9", InterpreterSource::Pyenv),
+            interpreter("/usr/bin/python3", "3.10.12", InterpreterSource::Path),
+            interpreter("/conda/bin/python", "3.13.0", InterpreterSource::Conda),
+            interpreter("/usr/local/bin/python3", "3.12.1", InterpreterSource::Path),
+        ];
+        sort_interpreters(&mut interpreters);
+        let paths: Vec<&str> = interpreters
+            .iter()
+            .map(|python| python.path.to_str().unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "/usr/bin/python3",
+                "/usr/local/bin/python3",
+                "/conda/bin/python",
+                "/pyenv/3.11/bin/python",
+            ]
+        );
+    }
+}
@@ -1,3 +1,7 @@
 pub mod check;
+pub mod files;
 pub mod install;
+pub mod interpreters;
+pub mod process;
 pub mod tools;
+pub mod venv;
@@ -0,0 +1,189 @@
+use crate::dry_run;
+use crate::error::{CliError, Result};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader};
+use std::process::{Command as ShellCommand, Stdio};
+
+/// Number of stderr lines kept to report a failed command.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Runs a command with stdout attached to the terminal and checks its exit status.
+///
+/// Stderr is mirrored to the terminal as it arrives, and its last lines are kept so that a failure
+/// can be reported with the actual error message. In dry-run mode the command is only recorded.
+///
+/// # Arguments
+///
+/// * `command` - The command to run, with its arguments and working directory already set.
+///
+/// # Returns
+///
+/// * `Ok(())` if the command exited successfully,
+/// * `Err(CliError::ToolMissing)` if the program could not be found,
+/// * `Err(CliError::CommandFailed)` if it exited with a failure status.
+///
+/// # Example
+///
+/// ```
+/// run_command(ShellCommand::new("pip").args(["install", "-r", "requirements.txt"]))?;
+/// ```
+pub fn run_command(command: &mut ShellCommand) -> Result<()> {
+    if dry_run::is_enabled() {
+        return dry_run::run(command);
+    }
+
+    let cmd = describe_command(command);
+
+    let mut child = command
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| spawn_error(command, &cmd, err))?;
+
+    let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(|line| line.ok()) {
+            eprintln!("{}", line);
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|err| CliError::io(format!("exécution de `{}`", cmd), err))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(CliError::CommandFailed {
+            cmd,
+            status: status.code(),
+            stderr: Vec::from(tail).join("\n"),
+        })
+    }
+}
+
+/// Runs a command whose output is read by the CLI, e.g. `pip freeze`, and returns its stdout.
+/// In dry-run mode the command is only recorded and the output is empty.
+///
+/// # Arguments
+///
+/// * `command` - The command to run, with its arguments and working directory already set.
+///
+/// # Returns
+///
+/// * `Ok(stdout)` if the command exited successfully,
+/// * `Err(CliError::ToolMissing)` if the program could not be found,
+/// * `Err(CliError::CommandFailed)` with the end of its stderr if it exited with a failure status.
+///
+/// # Example
+///
+/// ```
+/// let installed = command_output(python.pip().arg("freeze"))?;
+/// ```
+pub fn command_output(command: &mut ShellCommand) -> Result<String> {
+    if dry_run::is_enabled() {
+        dry_run::run(command)?;
+        return Ok(String::new());
+    }
+
+    let cmd = describe_command(command);
+
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| spawn_error(command, &cmd, err))?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    Err(CliError::CommandFailed {
+        cmd,
+        status: output.status.code(),
+        stderr: lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n"),
+    })
+}
+
+/// Formats a command as it would be typed in a shell, e.g. `pip install -r requirements.txt`.
+/// Arguments are quoted when needed, so the line can be pasted in a POSIX shell.
+pub fn describe_command(command: &ShellCommand) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| shell_quote(&part.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quotes a word for a POSIX shell, unless it only contains characters the shell leaves alone.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(shell_quote("Django>=5.2,<5.3"), "'Django>=5.2,<5.3'");
+/// ```
+pub fn shell_quote(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !word.is_empty() && word.chars().all(is_safe) {
+        return word.to_string();
+    }
+    // Dans des apostrophes, seule l'apostrophe elle-même doit être échappée
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
+
+fn spawn_error(command: &ShellCommand, cmd: &str, err: io::Error) -> CliError {
+    if err.kind() == io::ErrorKind::NotFound {
+        CliError::ToolMissing {
+            tool: command.get_program().to_string_lossy().into_owned(),
+            hint: "installez-le ou vérifiez votre PATH".to_string(),
+        }
+    } else {
+        CliError::io(format!("lancement de `{}`", cmd), err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_commands_for_a_shell() {
+        let mut command = ShellCommand::new(".venv/bin/python");
+        command.args([
+            "-m",
+            "pip",
+            "install",
+            "Django>=5.2,<5.3",
+            "-r",
+            "requirements.txt",
+        ]);
+        assert_eq!(
+            describe_command(&command),
+            ".venv/bin/python -m pip install 'Django>=5.2,<5.3' -r requirements.txt"
+        );
+
+        let mut command = ShellCommand::new("python");
+        command.args(["-c", "import secrets; print(secrets.token_urlsafe(50))"]);
+        assert_eq!(
+            describe_command(&command),
+            "python -c 'import secrets; print(secrets.token_urlsafe(50))'"
+        );
+    }
+
+    #[test]
+    fn quotes_shell_words() {
+        assert_eq!(
+            shell_quote("--find-links=/tmp/wheels"),
+            "--find-links=/tmp/wheels"
+        );
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("my project"), "'my project'");
+        assert_eq!(shell_quote("psycopg[binary]"), "'psycopg[binary]'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+}
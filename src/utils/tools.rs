@@ -1,4 +1,7 @@
 use crate::commands::setup_command;
+use crate::error::{CliError, Result};
+use clap::ValueEnum;
+use std::fmt::Display;
 use std::io::{self, Write};
 
 /// Displays a prompt with a list of options and lets the user select one.
@@ -10,36 +13,79 @@ use std::io::{self, Write};
 ///
 /// # Returns
 ///
-/// * A `String` containing the selected option.
+/// * A `String` containing the selected option, or `CliError::Io` if stdin is closed.
 ///
 /// # Example
 ///
 /// ```
-/// let choice = select_option("Please choose an option", &["Option 1", "Option 2", "Option 3"]);
+/// let choice = select_option("Please choose an option", &["Option 1", "Option 2", "Option 3"])?;
 /// println!("You selected: {}", choice);
 /// ```
-pub fn select_option(prompt: &str, options: &[&str]) -> String {
-    println!("{}:", prompt);
-    for (i, option) in options.iter().enumerate() {
-        println!("{}. {}", i + 1, option);
-    }
-    print!("Please make a choice: ");
-    io::stdout().flush().unwrap();
-
-    let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line");
-    let choice = input.trim().parse::<usize>().unwrap_or(0);
+pub fn select_option(prompt: &str, options: &[&str]) -> Result<String> {
+    loop {
+        println!("{}:", prompt);
+        for (i, option) in options.iter().enumerate() {
+            println!("{}. {}", i + 1, option);
+        }
+        let input = read_input("Please make a choice: ")?;
+        let choice = input.trim().parse::<usize>().unwrap_or(0);
 
-    if choice > 0 && choice <= options.len() {
-        options[choice - 1].to_string()
-    } else {
+        if choice > 0 && choice <= options.len() {
+            return Ok(options[choice - 1].to_string());
+        }
         println!("Invalid choice, please try again.");
-        select_option(prompt, options)
     }
 }
 
+/// Displays the variants of a value enum as a numbered list and lets the user select one.
+///
+/// # Arguments
+///
+/// * `prompt` - A string slice that holds the prompt message.
+///
+/// # Returns
+///
+/// * The selected variant; its `Display` label is what the user sees.
+///
+/// # Example
+///
+/// ```
+/// let db_type: DbType = select_choice("Type de base de données")?;
+/// println!("You selected: {}", db_type);
+/// ```
+pub fn select_choice<T: ValueEnum + Display>(prompt: &str) -> Result<T> {
+    select_from(prompt, T::value_variants())
+}
+
+/// Displays a list of values as a numbered list and lets the user select one.
+///
+/// # Arguments
+///
+/// * `prompt` - A string slice that holds the prompt message.
+/// * `choices` - The values to choose from, shown with their `Display` label.
+///
+/// # Returns
+///
+/// * The selected value.
+///
+/// # Example
+///
+/// ```
+/// let manager = select_from("Gestionnaire de paquets", &[PackageManager::Pip, PackageManager::Uv])?;
+/// println!("You selected: {}", manager);
+/// ```
+pub fn select_from<T: Clone + Display>(prompt: &str, choices: &[T]) -> Result<T> {
+    let labels: Vec<String> = choices.iter().map(|choice| choice.to_string()).collect();
+    let options: Vec<&str> = labels.iter().map(String::as_str).collect();
+
+    let choice = select_option(prompt, &options)?;
+    let index = labels
+        .iter()
+        .position(|label| *label == choice)
+        .unwrap_or(0);
+    Ok(choices[index].clone())
+}
+
 /// Prompts the user to enter a name for the Django project.
 ///
 /// # Returns
@@ -49,19 +95,62 @@ pub fn select_option(prompt: &str, options: &[&str]) -> String {
 /// # Example
 ///
 /// ```
-/// let project_name = get_project_name();
+/// let project_name = get_project_name()?;
 /// println!("Your Django project name is: {}", project_name);
 /// ```
-pub fn get_project_name() -> String {
-    print!("Enter your Django project name: ");
-    io::stdout().flush().unwrap();
+pub fn get_project_name() -> Result<String> {
+    let project_name = read_input("Enter your Django project name: ")?;
+    Ok(project_name.trim().to_string())
+}
+
+/// Asks a yes/no question; anything but an explicit yes (`o`, `oui`, `y`, `yes`) is a no.
+///
+/// # Arguments
+///
+/// * `prompt` - The question, without the `[o/N]` suffix.
+///
+/// # Returns
+///
+/// * `true` if the user answered yes, or `CliError::Io` if stdin is closed.
+///
+/// # Example
+///
+/// ```
+/// if confirm("Installer Python avec `sudo dnf install -y python3` ?")? {
+///     println!("Installation...");
+/// }
+/// ```
+pub fn confirm(prompt: &str) -> Result<bool> {
+    let answer = read_input(&format!("{} [o/N] ", prompt))?;
+    Ok(matches!(
+        answer.trim().to_lowercase().as_str(),
+        "o" | "oui" | "y" | "yes"
+    ))
+}
+
+/// Prints `prompt` and reads one line from stdin. A closed stdin is an error rather than an empty answer,
+/// so that prompts never loop forever in a non-interactive shell.
+fn read_input(prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    io::stdout()
+        .flush()
+        .map_err(|err| CliError::io("affichage de l'invite", err))?;
 
-    let mut project_name = String::new();
-    io::stdin()
-        .read_line(&mut project_name)
-        .expect("Failed to read line");
+    let mut input = String::new();
+    let read = io::stdin()
+        .read_line(&mut input)
+        .map_err(|err| CliError::io("lecture de l'entrée standard", err))?;
+    if read == 0 {
+        return Err(CliError::io(
+            "lecture de l'entrée standard",
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "entrée fermée, utilisez --config pour un mode non interactif",
+            ),
+        ));
+    }
 
-    project_name.trim().to_string()
+    Ok(input)
 }
 
 /// Sanitizes the project name by replacing invalid characters with underscores.
@@ -85,7 +174,7 @@ pub fn sanitize_project_name(name: &str) -> String {
     let sanitized = name
         .trim()
         .replace(|c: char| !c.is_alphanumeric() && c != '_', "_");
-    if sanitized.chars().next().unwrap().is_ascii_digit() {
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
         format!("_{}", sanitized)
     } else {
         sanitized
@@ -0,0 +1,258 @@
+use crate::error::{CliError, Result};
+use crate::utils::process::run_command;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command as ShellCommand, Stdio};
+
+/// Location of the interpreter inside a virtual environment.
+#[cfg(windows)]
+const VENV_PYTHON: &str = "Scripts/python.exe";
+
+#[cfg(not(windows))]
+const VENV_PYTHON: &str = "bin/python";
+
+/// Directory of the scripts of a virtual environment.
+#[cfg(windows)]
+const VENV_SCRIPTS: &str = "Scripts";
+
+#[cfg(not(windows))]
+const VENV_SCRIPTS: &str = "bin";
+
+/// Shell command activating a virtual environment, followed by its directory.
+#[cfg(windows)]
+const ACTIVATE: (&str, &str) = ("", "Scripts\\activate");
+
+#[cfg(not(windows))]
+const ACTIVATE: (&str, &str) = ("source ", "bin/activate");
+
+/// Python interpreter through which every `python`, `pip` and `django-admin` invocation goes,
+/// either a virtual environment or the system interpreter (`--no-venv`).
+#[derive(Clone, Debug)]
+pub struct PythonEnv {
+    python: PathBuf,
+    venv: Option<PathBuf>,
+    /// Directory of wheels to install from instead of the package index (`--offline`).
+    wheelhouse: Option<PathBuf>,
+}
+
+impl PythonEnv {
+    /// The system interpreter, e.g. `python3` looked up on the PATH, or the path of an interpreter.
+    pub fn system(python: impl Into<PathBuf>) -> PythonEnv {
+        PythonEnv {
+            python: python.into(),
+            venv: None,
+            wheelhouse: None,
+        }
+    }
+
+    /// The interpreter of an existing virtual environment. The path is made absolute, as
+    /// commands using it may run from another directory.
+    pub fn venv(path: &Path) -> PythonEnv {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        PythonEnv {
+            python: path.join(VENV_PYTHON),
+            venv: Some(path),
+            wheelhouse: None,
+        }
+    }
+
+    /// Creates a virtual environment with `python -m venv`.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_python` - The interpreter the environment is based on, as chosen by `install_python`.
+    /// * `path` - The directory of the environment.
+    ///
+    /// # Returns
+    ///
+    /// * The environment, or the error of `python -m venv`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let python = PythonEnv::create(Path::new("python3"), &project_dir.join(".venv"))?;
+    /// run_command(python.pip().args(["install", "-r", "requirements.txt"]))?;
+    /// ```
+    pub fn create(base_python: &Path, path: &Path) -> Result<PythonEnv> {
+        println!("Création de l'environnement virtuel {}...", path.display());
+
+        run_command(
+            ShellCommand::new(base_python)
+                .arg("-m")
+                .arg("venv")
+                .arg(path),
+        )?;
+
+        Ok(PythonEnv::venv(path))
+    }
+
+    /// Installs packages from the wheels of `wheelhouse` only, without contacting the package
+    /// index. The path is made absolute, as pip may run from another directory.
+    pub fn with_wheelhouse(mut self, wheelhouse: Option<&Path>) -> PythonEnv {
+        self.wheelhouse =
+            wheelhouse.map(|path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()));
+        self
+    }
+
+    /// The interpreter to use for an existing project: its `.venv` if there is one, then the
+    /// activated virtual environment (`VIRTUAL_ENV`), then `python` from the PATH.
+    pub fn detect(project_dir: &Path) -> PythonEnv {
+        let local_venv = project_dir.join(".venv");
+        if local_venv.join("pyvenv.cfg").is_file() {
+            return PythonEnv::venv(&local_venv);
+        }
+
+        match env::var_os("VIRTUAL_ENV") {
+            Some(path) => PythonEnv::venv(Path::new(&path)),
+            None => PythonEnv::system("python"),
+        }
+    }
+
+    /// Directory of the virtual environment, `None` for the system interpreter.
+    pub fn venv_path(&self) -> Option<&Path> {
+        self.venv.as_deref()
+    }
+
+    /// Wheel directory used instead of the package index, `None` when online.
+    pub fn wheelhouse(&self) -> Option<&Path> {
+        self.wheelhouse.as_deref()
+    }
+
+    /// Path of the interpreter executable.
+    pub fn executable(&self) -> &Path {
+        &self.python
+    }
+
+    /// Command to type to activate the virtual environment in a shell.
+    pub fn activate_command(&self) -> Option<String> {
+        let (prefix, script) = ACTIVATE;
+        let venv = self.venv.as_ref()?;
+        Some(format!("{}{}", prefix, venv.join(script).display()))
+    }
+
+    /// `python`
+    pub fn python(&self) -> ShellCommand {
+        ShellCommand::new(&self.python)
+    }
+
+    /// `python -m pip`
+    pub fn pip(&self) -> ShellCommand {
+        let mut command = self.python();
+        command.arg("-m").arg("pip");
+        command
+    }
+
+    /// `python -m pip install`, with `--no-index --find-links <wheelhouse>` when offline.
+    pub fn pip_install(&self) -> ShellCommand {
+        self.pip_from_index("install")
+    }
+
+    /// `python -m pip download`, from the wheelhouse only when offline.
+    pub fn pip_download(&self) -> ShellCommand {
+        self.pip_from_index("download")
+    }
+
+    fn pip_from_index(&self, subcommand: &str) -> ShellCommand {
+        let mut command = self.pip();
+        command.arg(subcommand);
+        if let Some(wheelhouse) = &self.wheelhouse {
+            command
+                .arg("--no-index")
+                .arg("--find-links")
+                .arg(wheelhouse);
+        }
+        command
+    }
+
+    /// `python -m django`, equivalent to the `django-admin` script.
+    pub fn django_admin(&self) -> ShellCommand {
+        let mut command = self.python();
+        command.arg("-m").arg("django");
+        command
+    }
+
+    /// An external tool (`uv`, `poetry`, ...) run against this environment: `VIRTUAL_ENV` and
+    /// `UV_PROJECT_ENVIRONMENT` point to the virtual environment, if any.
+    pub fn tool(&self, program: &str) -> ShellCommand {
+        let mut command = ShellCommand::new(program);
+        if let Some(venv) = &self.venv {
+            command
+                .env("VIRTUAL_ENV", venv)
+                .env("UV_PROJECT_ENVIRONMENT", venv);
+        }
+        command
+    }
+
+    /// Whether `module` can be imported by the interpreter.
+    pub fn has_module(&self, module: &str) -> bool {
+        self.python()
+            .arg("-c")
+            .arg(format!("import {}", module))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    /// Version of the Django importable by the interpreter, e.g. `4.2.8`, or `None` if there is none.
+    pub fn django_version(&self) -> Option<String> {
+        let output = self
+            .python()
+            .arg("-c")
+            .arg("import django; print(django.get_version())")
+            .stderr(Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!version.is_empty()).then_some(version)
+    }
+}
+
+/// Points a virtual environment that was moved along with its project to its new location.
+///
+/// `python -m venv` records the absolute path of the environment in `pyvenv.cfg`, in the
+/// activation scripts and in the shebang of every installed script. Text files of the scripts
+/// directory that mention the old project directory are rewritten; binaries, such as the script
+/// launchers of Windows, are left as they are, `python -m` keeps working either way.
+///
+/// # Arguments
+///
+/// * `venv` - The virtual environment, at its new location.
+/// * `from` - The absolute path of the directory it was created in, e.g. the staged project.
+/// * `to` - The absolute path that replaces `from`.
+///
+/// # Returns
+///
+/// * `Ok(())`, or `CliError::Io` if a file cannot be read or written.
+pub fn relocate_venv(venv: &Path, from: &Path, to: &Path) -> Result<()> {
+    let (from, to) = (from.to_string_lossy(), to.to_string_lossy());
+
+    let scripts_dir = venv.join(VENV_SCRIPTS);
+    let scripts = fs::read_dir(&scripts_dir)
+        .map_err(|err| CliError::io(format!("lecture de {}", scripts_dir.display()), err))?;
+    let mut files = vec![venv.join("pyvenv.cfg")];
+    for entry in scripts {
+        let entry = entry
+            .map_err(|err| CliError::io(format!("lecture de {}", scripts_dir.display()), err))?;
+        // Les liens vers l'interpréteur de base ne désignent pas l'environnement
+        if entry.file_type().is_ok_and(|file_type| file_type.is_file()) {
+            files.push(entry.path());
+        }
+    }
+
+    for file in files {
+        let bytes = fs::read(&file)
+            .map_err(|err| CliError::io(format!("lecture de {}", file.display()), err))?;
+        let Ok(text) = String::from_utf8(bytes) else {
+            continue;
+        };
+        if text.contains(from.as_ref()) {
+            fs::write(&file, text.replace(from.as_ref(), &to))
+                .map_err(|err| CliError::io(format!("écriture de {}", file.display()), err))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,502 @@
+//! Verification of a generated project.
+//!
+//! While the project is generated, every step is announced with `step`: the files it creates or
+//! modifies are detected by comparing snapshots of the project directory, including those written
+//! by `django-admin` and `manage.py`. `verify_project` then byte-compiles the Python files and
+//! runs `manage.py check` and `manage.py makemigrations --check --dry-run`, and reports each
+//! failure with the steps that produced the offending file.
+
+use crate::doctor::{print_table, Check, Status};
+use crate::dry_run;
+use crate::error::{CliError, Result};
+use crate::project::ProjectLayout;
+use similar::{ChangeTag, TextDiff};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Mutex;
+
+/// Compiles the files given as arguments without writing `.pyc` files, and prints one
+/// `path<TAB>line<TAB>error` line per file that does not compile.
+const COMPILE_SCRIPT: &str = r#"
+import sys
+for path in sys.argv[1:]:
+    try:
+        with open(path, 'rb') as source:
+            compile(source.read(), path, 'exec')
+    except Exception as err:
+        print('%s\t%s\t%s: %s' % (path, getattr(err, 'lineno', None) or '', type(err).__name__, getattr(err, 'msg', err)))
+"#;
+
+/// Directories that are not part of the generated sources.
+const IGNORED_DIRS: &[&str] = &[".venv", ".git", "__pycache__", "node_modules"];
+
+static TRACKER: Mutex<Option<Tracker>> = Mutex::new(None);
+
+struct Tracker {
+    root: PathBuf,
+    step: String,
+    files: BTreeMap<PathBuf, Origin>,
+}
+
+/// The steps that wrote a file of the project.
+#[derive(Default)]
+struct Origin {
+    content: String,
+    /// Steps that created or modified the file, in order.
+    steps: Vec<String>,
+    /// Step that wrote each line of the current content.
+    lines: Vec<String>,
+}
+
+/// A problem found in the generated project, located in a file when possible.
+struct Failure {
+    file: Option<PathBuf>,
+    line: Option<u32>,
+    message: String,
+}
+
+impl Tracker {
+    /// Attributes the files, and the lines, changed since the previous snapshot to the current
+    /// step.
+    fn flush(&mut self) {
+        for (path, content) in snapshot(&self.root) {
+            let origin = self.files.entry(path).or_default();
+            if origin.content == content && !origin.steps.is_empty() {
+                continue;
+            }
+
+            // Les lignes inchangées gardent leur étape, les nouvelles reviennent à l'étape courante
+            let diff = TextDiff::from_lines(&origin.content, &content);
+            let mut lines = Vec::new();
+            for change in diff.iter_all_changes() {
+                match (change.tag(), change.old_index()) {
+                    (ChangeTag::Equal, Some(index)) => {
+                        lines.push(origin.lines.get(index).cloned().unwrap_or_default())
+                    }
+                    (ChangeTag::Delete, _) => {}
+                    _ => lines.push(self.step.clone()),
+                }
+            }
+
+            if origin.steps.last() != Some(&self.step) {
+                origin.steps.push(self.step.clone());
+            }
+            origin.lines = lines;
+            origin.content = content;
+        }
+    }
+}
+
+/// Starts recording which step writes which file of the project generated in `root`. Does
+/// nothing in a dry run, where no file is written.
+pub fn track(root: &Path) {
+    if dry_run::is_enabled() {
+        return;
+    }
+
+    *lock() = Some(Tracker {
+        root: root.to_path_buf(),
+        step: String::new(),
+        files: BTreeMap::new(),
+    });
+}
+
+/// Starts a generation step: the files changed from now on are attributed to it.
+///
+/// # Example
+///
+/// ```
+/// verify::step("fichiers Docker");
+/// generate_docker_files(layout, config, python)?;
+/// ```
+pub fn step(name: impl Into<String>) {
+    if let Some(tracker) = lock().as_mut() {
+        tracker.flush();
+        tracker.step = name.into();
+    }
+}
+
+/// Byte-compiles the Python files of the project, then runs `manage.py check` and
+/// `manage.py makemigrations --check --dry-run` with the interpreter of the project.
+///
+/// # Arguments
+///
+/// * `layout` - The generated project, with its virtual environment.
+///
+/// # Returns
+///
+/// * `Ok(())` if every verification passed, or in a dry run,
+/// * `Err(CliError::ChecksFailed)` with the number of failed verifications otherwise.
+///
+/// # Example
+///
+/// ```
+/// verify::track(&layout.root);
+/// verify::step("django-admin startproject");
+/// create_django_project(&layout, django)?;
+/// verify_project(&layout)?;
+/// ```
+pub fn verify_project(layout: &ProjectLayout) -> Result<()> {
+    if dry_run::is_enabled() {
+        return Ok(());
+    }
+
+    let files = match lock().take() {
+        Some(mut tracker) => {
+            tracker.flush();
+            tracker.files
+        }
+        None => BTreeMap::new(),
+    };
+
+    println!("Vérification du projet généré...");
+    let checks = [
+        check_compile(layout, &files),
+        check_django(layout, &files),
+        check_migrations(layout, &files),
+    ];
+    print_table(&checks);
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == Status::Fail)
+        .count();
+    if failed > 0 {
+        return Err(CliError::ChecksFailed(failed));
+    }
+    Ok(())
+}
+
+fn check_compile(layout: &ProjectLayout, files: &BTreeMap<PathBuf, Origin>) -> Check {
+    let sources: Vec<PathBuf> = snapshot(&layout.root)
+        .into_keys()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "py"))
+        .collect();
+
+    let output = layout
+        .python
+        .python()
+        .arg("-c")
+        .arg(COMPILE_SCRIPT)
+        .args(&sources)
+        .current_dir(&layout.root)
+        .output();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => return failed_to_run("compile", "Compilation Python", &output),
+        Err(err) => {
+            return Check::new(
+                "compile",
+                "Compilation Python",
+                Status::Fail,
+                format!("Python n'a pas pu être lancé : {}", err),
+            )
+        }
+    };
+
+    let failures: Vec<Failure> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let file = fields.next()?;
+            let line = fields.next()?.parse().ok();
+            Some(Failure {
+                file: Some(PathBuf::from(file)),
+                line,
+                message: fields.next()?.to_string(),
+            })
+        })
+        .collect();
+
+    if failures.is_empty() {
+        Check::new(
+            "compile",
+            "Compilation Python",
+            Status::Pass,
+            format!("{} fichier(s) compilé(s)", sources.len()),
+        )
+    } else {
+        Check::new(
+            "compile",
+            "Compilation Python",
+            Status::Fail,
+            describe_failures(&failures, files),
+        )
+    }
+}
+
+fn check_django(layout: &ProjectLayout, files: &BTreeMap<PathBuf, Origin>) -> Check {
+    let Some(output) = run_manage_py(layout, &["check"]) else {
+        return manage_py_missing("check", "manage.py check");
+    };
+    if output.status.success() {
+        return Check::new("check", "manage.py check", Status::Pass, "aucune erreur");
+    }
+
+    let failures = parse_errors(layout, &output, files);
+    Check::new(
+        "check",
+        "manage.py check",
+        Status::Fail,
+        describe_failures(&failures, files),
+    )
+}
+
+fn check_migrations(layout: &ProjectLayout, files: &BTreeMap<PathBuf, Origin>) -> Check {
+    let Some(output) = run_manage_py(layout, &["makemigrations", "--check", "--dry-run"]) else {
+        return manage_py_missing("migrations", "Migrations");
+    };
+    if output.status.success() {
+        return Check::new("migrations", "Migrations", Status::Pass, "à jour");
+    }
+
+    // Des modèles sans migration : "Migrations for 'app':" pour chaque application
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut failures: Vec<Failure> = stdout
+        .lines()
+        .filter_map(|line| {
+            let app = line
+                .trim()
+                .strip_prefix("Migrations for '")?
+                .strip_suffix("':")?;
+            Some(Failure {
+                file: Some(Path::new(app).join("models.py")),
+                line: None,
+                message: "modèles sans migration".to_string(),
+            })
+        })
+        .collect();
+    if failures.is_empty() {
+        failures = parse_errors(layout, &output, files);
+    }
+
+    Check::new(
+        "migrations",
+        "Migrations",
+        Status::Fail,
+        describe_failures(&failures, files),
+    )
+}
+
+fn run_manage_py(layout: &ProjectLayout, args: &[&str]) -> Option<Output> {
+    layout
+        .python
+        .python()
+        .arg("manage.py")
+        .args(args)
+        .current_dir(&layout.root)
+        .output()
+        .ok()
+}
+
+/// Errors of a failed `manage.py` command: the project frame of a traceback, or the errors of
+/// the system check framework, e.g. `authentication.CustomUser: (auth.E003) ...`.
+fn parse_errors(
+    layout: &ProjectLayout,
+    output: &Output,
+    files: &BTreeMap<PathBuf, Origin>,
+) -> Vec<Failure> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("erreur inconnue")
+        .to_string();
+
+    // Dans une trace d'appels, la dernière ligne `File "...", line N` qui désigne le projet
+    let root = fs::canonicalize(&layout.root).unwrap_or_else(|_| layout.root.clone());
+    let frame = stderr.lines().rev().find_map(|line| {
+        let rest = line.trim().strip_prefix("File \"")?;
+        let (path, rest) = rest.split_once('"')?;
+        let line = rest
+            .strip_prefix(", line ")?
+            .split(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        let path = Path::new(path);
+        let path = path.strip_prefix(&root).unwrap_or(path);
+        files.contains_key(path).then(|| (path.to_path_buf(), line))
+    });
+    if let Some((file, line)) = frame {
+        return vec![Failure {
+            file: Some(file),
+            line: Some(line),
+            message,
+        }];
+    }
+
+    let settings = layout
+        .settings_path()
+        .strip_prefix(&layout.root)
+        .map(Path::to_path_buf)
+        .ok();
+    let checks: Vec<Failure> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.contains(": (") && (line.contains(".E") || line.contains(".C")))
+        .map(|line| {
+            let label = line.split(['.', ':']).next().unwrap_or_default();
+            let file = match label {
+                "?" => settings.clone(),
+                label => Some(Path::new(label).join("models.py")),
+            };
+            Failure {
+                file,
+                line: None,
+                message: line.to_string(),
+            }
+        })
+        .collect();
+    if !checks.is_empty() {
+        return checks;
+    }
+
+    vec![Failure {
+        file: None,
+        line: None,
+        message,
+    }]
+}
+
+/// The failures, separated by `;`: the file and line, the error, then the step that wrote the
+/// line, or the steps that wrote the file when the line is not known.
+fn describe_failures(failures: &[Failure], files: &BTreeMap<PathBuf, Origin>) -> String {
+    failures
+        .iter()
+        .map(|failure| {
+            let Some(file) = &failure.file else {
+                return failure.message.clone();
+            };
+            let location = match failure.line {
+                Some(line) => format!("{}, ligne {}", file.display(), line),
+                None => file.display().to_string(),
+            };
+
+            let origin = files.get(file);
+            let line_step = origin
+                .zip(failure.line)
+                .and_then(|(origin, line)| origin.lines.get(line.checked_sub(1)? as usize))
+                .filter(|step| !step.is_empty());
+            let origin = match (line_step, origin) {
+                (Some(step), _) => format!("ligne écrite par : {}", step),
+                (None, Some(origin)) => format!("fichier écrit par : {}", origin.steps.join(", ")),
+                (None, None) => "fichier non généré".to_string(),
+            };
+            format!("{} : {} ({})", location, failure.message, origin)
+        })
+        .collect::<Vec<_>>()
+        .join(" ; ")
+}
+
+fn failed_to_run(id: &'static str, name: &'static str, output: &Output) -> Check {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Check::new(
+        id,
+        name,
+        Status::Fail,
+        stderr.lines().last().unwrap_or("erreur inconnue").trim(),
+    )
+}
+
+fn manage_py_missing(id: &'static str, name: &'static str) -> Check {
+    Check::new(id, name, Status::Fail, "Python n'a pas pu lancer manage.py")
+}
+
+/// Content of every file of the project, by path relative to `root`.
+fn snapshot(root: &Path) -> BTreeMap<PathBuf, String> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                // Un environnement virtuel placé ailleurs que dans .venv a un pyvenv.cfg
+                let ignored = IGNORED_DIRS.iter().any(|name| entry.file_name() == *name)
+                    || path.join("pyvenv.cfg").is_file();
+                if !ignored {
+                    dirs.push(path);
+                }
+                continue;
+            }
+            let Ok(content) = fs::read(&path) else {
+                continue;
+            };
+            if let Ok(relative) = path.strip_prefix(root) {
+                let content = String::from_utf8_lossy(&content).into_owned();
+                files.insert(relative.to_path_buf(), content);
+            }
+        }
+    }
+
+    files
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Tracker>> {
+    TRACKER.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(file: &str, line: Option<u32>) -> Failure {
+        Failure {
+            file: Some(PathBuf::from(file)),
+            line,
+            message: "NameError".to_string(),
+        }
+    }
+
+    #[test]
+    fn failures_name_the_step_that_wrote_the_line() {
+        let root = std::env::temp_dir().join(format!("django_cli-verify-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".venv")).unwrap();
+        let settings = root.join("settings.py");
+        let mut tracker = Tracker {
+            root: root.clone(),
+            step: "django-admin startproject".to_string(),
+            files: BTreeMap::new(),
+        };
+
+        fs::write(&settings, "DEBUG = True\nALLOWED_HOSTS = []\n").unwrap();
+        fs::write(root.join(".venv/pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+        tracker.flush();
+        tracker.step = "cache".to_string();
+        // Une ligne remplacée et une ligne ajoutée par la seconde étape
+        fs::write(
+            &settings,
+            "DEBUG = True\nALLOWED_HOSTS = ['*']\nCACHES = os.x\n",
+        )
+        .unwrap();
+        tracker.flush();
+        tracker.step = "fichiers Docker".to_string();
+        tracker.flush();
+        fs::remove_dir_all(&root).unwrap();
+
+        let describe = |failure| describe_failures(&[failure], &tracker.files);
+        assert_eq!(
+            describe(failure("settings.py", Some(1))),
+            "settings.py, ligne 1 : NameError (ligne écrite par : django-admin startproject)"
+        );
+        assert_eq!(
+            describe(failure("settings.py", Some(3))),
+            "settings.py, ligne 3 : NameError (ligne écrite par : cache)"
+        );
+        assert_eq!(
+            describe(failure("settings.py", None)),
+            "settings.py : NameError (fichier écrit par : django-admin startproject, cache)"
+        );
+        assert_eq!(
+            describe(failure("urls.py", Some(1))),
+            "urls.py, ligne 1 : NameError (fichier non généré)"
+        );
+        assert!(!tracker.files.keys().any(|path| path.starts_with(".venv")));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::config::{PackageManager, ProjectConfig};
+use crate::error::{CliError, Result};
+use crate::utils::files::create_dir;
+use crate::utils::install::{install_pip, DjangoRelease};
+use crate::utils::interpreters::Interpreter;
+use crate::utils::process::run_command;
+use crate::utils::venv::PythonEnv;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+/// Checks that a project can be installed offline from `wheelhouse`: the directory exists and
+/// the package manager can install from it (pip or uv).
+///
+/// # Returns
+///
+/// * `Ok(())`, or `CliError::InvalidConfig` listing every problem.
+pub fn check_wheelhouse(wheelhouse: &Path, package_manager: PackageManager) -> Result<()> {
+    let mut errors = Vec::new();
+    if !wheelhouse.is_dir() {
+        errors.push(format!(
+            "--wheelhouse : le dossier {} n'existe pas (créez-le avec `django_cli wheelhouse build`)",
+            wheelhouse.display()
+        ));
+    }
+    if !matches!(package_manager, PackageManager::Pip | PackageManager::Uv) {
+        errors.push(format!(
+            "--offline : {} ne peut pas installer depuis un dossier de wheels, utilisez pip ou uv",
+            package_manager
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(CliError::InvalidConfig(errors));
+    }
+    Ok(())
+}
+
+/// Downloads, or builds when only a source archive is published, the wheels of every dependency
+/// of a project, so that `django_cli new --offline --wheelhouse <output>` can run without network.
+///
+/// Wheels are chosen for the given interpreter: the offline installation must use the same
+/// Python version and platform. pip runs from a throwaway virtual environment created with that
+/// interpreter, so that nothing is installed into it.
+///
+/// # Arguments
+///
+/// * `config` - The project whose `requirements.txt` and development tools are downloaded.
+/// * `python` - The interpreter the project will use.
+/// * `django` - The Django release of the project, as chosen by `select_django_release`.
+/// * `output` - The wheelhouse directory, created if needed.
+///
+/// # Returns
+///
+/// * `Ok(())` once every wheel is in `output`,
+/// * `Err(CliError::CommandFailed)` if pip could not get one of them.
+///
+/// # Example
+///
+/// ```
+/// build_wheelhouse(&config, &python, django, Path::new("wheels"))?;
+/// ```
+pub fn build_wheelhouse(
+    config: &ProjectConfig,
+    python: &Interpreter,
+    django: &DjangoRelease,
+    output: &Path,
+) -> Result<()> {
+    let mut requirements = config.requirements(django);
+    requirements.extend(config.dev_requirements().into_iter().map(str::to_string));
+
+    println!(
+        "Téléchargement de {} dépendances et de leurs sous-dépendances dans {}...",
+        requirements.len(),
+        output.display()
+    );
+
+    create_dir(output)?;
+    let venv_dir = env::temp_dir().join(format!("django_cli-wheelhouse-{}", process::id()));
+    let built = build_wheels(python, &venv_dir, &requirements, output);
+
+    // L'environnement ne sert qu'à lancer pip
+    let _ = fs::remove_dir_all(&venv_dir);
+    built?;
+
+    println!(
+        "Wheels prêtes. Créez le projet avec : django_cli new --offline --wheelhouse {}",
+        output.display()
+    );
+    Ok(())
+}
+
+fn build_wheels(
+    python: &Interpreter,
+    venv_dir: &Path,
+    requirements: &[String],
+    output: &Path,
+) -> Result<()> {
+    let python = PythonEnv::create(&python.path, venv_dir)?;
+    install_pip(&python)?;
+
+    // `pip wheel` télécharge les wheels publiées et construit celles des archives sources
+    run_command(
+        python
+            .pip()
+            .arg("wheel")
+            .arg("--wheel-dir")
+            .arg(output)
+            .args(requirements),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(wheelhouse: &Path, package_manager: PackageManager) -> Vec<String> {
+        match check_wheelhouse(wheelhouse, package_manager) {
+            Ok(()) => Vec::new(),
+            Err(CliError::InvalidConfig(errors)) => errors,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn offline_installs_need_a_wheelhouse_and_pip_or_uv() {
+        let existing = env::temp_dir();
+        let missing = existing.join(format!("django_cli-no-wheels-{}", std::process::id()));
+
+        assert!(errors(&existing, PackageManager::Pip).is_empty());
+        assert!(errors(&existing, PackageManager::Uv).is_empty());
+        assert_eq!(
+            errors(&existing, PackageManager::Poetry),
+            ["--offline : Poetry ne peut pas installer depuis un dossier de wheels, utilisez pip ou uv"]
+        );
+        assert_eq!(
+            errors(&missing, PackageManager::Pdm),
+            [
+                format!(
+                    "--wheelhouse : le dossier {} n'existe pas (créez-le avec `django_cli wheelhouse build`)",
+                    missing.display()
+                ),
+                "--offline : PDM ne peut pas installer depuis un dossier de wheels, utilisez pip ou uv"
+                    .to_string(),
+            ]
+        );
+    }
+}